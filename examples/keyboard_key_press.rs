@@ -2,7 +2,7 @@ use log::{debug, error};
 use ramidier::enums::input_group::{KeyboardChannel, KeyboardInputGroup};
 use ramidier::enums::message_filter::MessageFilter;
 use ramidier::io::input::InputChannel;
-use ramidier::io::input_data::MidiInputData;
+use ramidier::io::input_data::{InputAction, MidiInputData};
 use std::error::Error;
 use std::io::stdin;
 
@@ -34,11 +34,10 @@ fn run() -> Result<(), Box<dyn Error>> {
 
 pub fn listener_logic(stamp: u64, msg: &MidiInputData<KeyboardInputGroup>) {
     println!("{stamp}: {msg:?}");
-    if msg.value > 0 {
-        let KeyboardInputGroup::Key(k) = msg.input_group;
-        debug!("Key pressed: {k:?}");
-    } else {
-        let KeyboardInputGroup::Key(k) = msg.input_group;
-        debug!("Key released: {k:?}");
+    let KeyboardInputGroup::Key(k) = msg.input_group;
+    match msg.action {
+        InputAction::Pressed { velocity } => debug!("Key pressed: {k:?} with velocity {velocity}"),
+        InputAction::Released { .. } => debug!("Key released: {k:?}"),
+        InputAction::Changed { .. } => (),
     }
 }
@@ -2,7 +2,7 @@ use ramidier::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
 use ramidier::enums::led_light::color::LedColor;
 use ramidier::enums::led_light::mode::LedMode;
 use ramidier::enums::message_filter::MessageFilter;
-use ramidier::io::input::InputChannel;
+use ramidier::io::device::ApcKey25;
 use ramidier::io::input_data::MidiInputData;
 use ramidier::io::output::ChannelOutput;
 use std::error::Error;
@@ -17,21 +17,13 @@ fn main() {
 
 fn run() -> Result<(), Box<dyn Error>> {
     let mut input = String::new();
-    // Setup MIDI Input
-    let midi_in = InputChannel::builder()
-        .port(2)
+    // Setup MIDI Input and Output
+    let mut device = ApcKey25::builder()
         .msg_to_ignore(MessageFilter::None)
         .build()?;
 
-    // Setup MIDI Output
-    let mut midi_out = ChannelOutput::builder()
-        .port(2)
-        .initialize_note_led(true)
-        .build()?;
-
-    let _conn_in = midi_in.listen(
-        Some("midir-read-input"),
-        move |stamp, rx_data, ()| listener_logic(&mut midi_out, stamp, &rx_data),
+    device.listen(
+        move |stamp, rx_data, midi_out, ()| listener_logic(midi_out, stamp, &rx_data),
         (),
         PadsAndKnobsChannel,
     )?;
@@ -46,7 +38,7 @@ pub fn listener_logic(
     msg: &MidiInputData<PadsAndKnobsInputGroup>,
 ) {
     println!("{stamp}: {msg:?}");
-    if msg.value > 0 {
+    if msg.is_pressed() {
         if let PadsAndKnobsInputGroup::Pads(k) = msg.input_group {
             let _ = midi_out.set_pad_led(LedMode::On100Percent, k, LedColor::Green);
         }
@@ -14,10 +14,7 @@ fn main() {
 
 fn run() -> Result<(), Box<dyn Error>> {
     // Setup MIDI Output
-    let mut midi_out = ChannelOutput::builder()
-        .port(2)
-        .initialize_note_led(true)
-        .build()?;
+    let mut midi_out = ChannelOutput::builder().initialize_note_led(true).build()?;
 
     match midi_out.set_all_pads_color(LedMode::On100Percent, LedColor::Green) {
         Ok(()) => println!("Successfully set all pads color"),
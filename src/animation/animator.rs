@@ -0,0 +1,349 @@
+use crate::animation::effect::Effect;
+use crate::io::frame::{FrameBuffer, LedFrame};
+use crate::io::output::ChannelOutput;
+use crate::io::sync::lock;
+use crate::io::transport::MidiSink;
+use bon::bon;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Identifies an effect started with `Animator::play`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EffectId(u64);
+
+struct Playing {
+    id: EffectId,
+    started: Instant,
+    effect: Box<dyn Effect>,
+}
+
+struct Scene {
+    background: LedFrame,
+    effects: Vec<Playing>,
+    next_id: u64,
+    frame_interval: Duration,
+}
+
+impl Scene {
+    fn new(frame_rate: u32) -> Self {
+        Self {
+            background: LedFrame::default(),
+            effects: Vec::new(),
+            next_id: 0,
+            frame_interval: frame_interval(frame_rate),
+        }
+    }
+
+    fn start(&mut self, effect: Box<dyn Effect>, started: Instant) -> EffectId {
+        let id = EffectId(self.next_id);
+        self.next_id += 1;
+        self.effects.push(Playing {
+            id,
+            started,
+            effect,
+        });
+        id
+    }
+
+    /// Draws the background and every effect on the frame, dropping the effects that are over
+    fn render(&mut self, now: Instant, frame: &mut LedFrame) {
+        frame.clone_from(&self.background);
+        self.effects.retain_mut(|playing| {
+            playing
+                .effect
+                .render(now.saturating_duration_since(playing.started), frame)
+        });
+    }
+}
+
+fn frame_interval(frame_rate: u32) -> Duration {
+    Duration::from_secs(1) / frame_rate.max(1)
+}
+
+/// Software animations on top of `ChannelOutput`, rendered by a timer thread at a fixed frame rate.
+///
+/// Each frame starts from the background and every playing effect draws on top of it, in the order they were started.
+/// Only the lights that changed since the previous frame are sent, so a still scene costs nothing.
+/// The animator owns every light of the controller: draw static content in the background rather than on the output.
+/// Dropping the animator stops the thread
+/// ```Rust
+///let device = ApcKey25::builder().build()?;
+///let animator = Animator::builder().output(device.shared_output()).frame_rate(60).build();
+///animator.update_background(|frame| frame.fill_pads(LedMode::On10Percent, LedColor::Blue1));
+///let chase = animator.play(
+///    Chase::builder().path((0..8).collect()).color(LedColor::Red).step(Duration::from_millis(60)).build(),
+///);
+///animator.play(Fade::builder().pads(vec![39]).from(LedColor::Off).to(LedColor::White).duration(Duration::from_secs(2)).build());
+///animator.stop(chase);
+/// ```
+pub struct Animator {
+    scene: Arc<Mutex<Scene>>,
+    running: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+#[bon]
+impl Animator {
+    /// `frame_rate` is in frames per second, 30 by default
+    #[builder]
+    pub fn new<O>(output: Arc<Mutex<ChannelOutput<O>>>, frame_rate: Option<u32>) -> Self
+    where
+        O: MidiSink + Send + 'static,
+    {
+        let scene = Arc::new(Mutex::new(Scene::new(frame_rate.unwrap_or(30))));
+        let running = Arc::new(AtomicBool::new(true));
+        let worker = Ticker {
+            scene: Arc::clone(&scene),
+            running: Arc::clone(&running),
+            output,
+            frame_buffer: FrameBuffer::default(),
+        };
+        Self {
+            scene,
+            running,
+            worker: Some(thread::spawn(move || worker.run())),
+        }
+    }
+}
+
+impl Animator {
+    /// Starts the effect on the next frame
+    pub fn play<E>(&self, effect: E) -> EffectId
+    where
+        E: Effect + 'static,
+    {
+        lock(&self.scene).start(Box::new(effect), Instant::now())
+    }
+
+    /// Removes the effect, nothing happens if it is already over
+    pub fn stop(&self, id: EffectId) {
+        lock(&self.scene).effects.retain(|playing| playing.id != id);
+    }
+
+    pub fn stop_all(&self) {
+        lock(&self.scene).effects.clear();
+    }
+
+    #[must_use]
+    pub fn is_playing(&self, id: EffectId) -> bool {
+        lock(&self.scene)
+            .effects
+            .iter()
+            .any(|playing| playing.id == id)
+    }
+
+    /// Edits the lights shown below the effects, the change appears on the next frame
+    pub fn update_background<F>(&self, update: F)
+    where
+        F: FnOnce(&mut LedFrame),
+    {
+        update(&mut lock(&self.scene).background);
+    }
+
+    pub fn set_frame_rate(&self, frame_rate: u32) {
+        lock(&self.scene).frame_interval = frame_interval(frame_rate);
+    }
+}
+
+impl Drop for Animator {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+struct Ticker<O> {
+    scene: Arc<Mutex<Scene>>,
+    running: Arc<AtomicBool>,
+    output: Arc<Mutex<ChannelOutput<O>>>,
+    frame_buffer: FrameBuffer,
+}
+
+impl<O: MidiSink> Ticker<O> {
+    fn run(mut self) {
+        while self.running.load(Ordering::Relaxed) {
+            let tick = Instant::now();
+            let frame_interval = self.tick(tick);
+            thread::sleep(frame_interval.saturating_sub(tick.elapsed()));
+        }
+    }
+
+    /// Renders the frame at `now` and sends what changed, returns the time until the next frame
+    fn tick(&mut self, now: Instant) -> Duration {
+        let frame_interval = {
+            let mut scene = lock(&self.scene);
+            scene.render(now, &mut self.frame_buffer);
+            scene.frame_interval
+        };
+        // A failed flush invalidates the buffer, the whole frame is sent again on the next tick
+        let _ = self.frame_buffer.flush(&mut lock(&self.output));
+        frame_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Animator, Scene, Ticker, frame_interval};
+    use crate::animation::effect::Effect;
+    use crate::enums::button::led_button::LedButton;
+    use crate::enums::button::pads::{PAD_COUNT, PadKey};
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::io::frame::{FrameBuffer, LedFrame, PadLed};
+    use crate::io::output::ChannelOutput;
+    use crate::io::sync::lock;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    const fn lit(mode: LedMode, color: LedColor) -> PadLed {
+        PadLed { mode, color }
+    }
+
+    /// Lights the pads for `lasts`, then is over
+    fn paint(pads: &'static [u8], color: LedColor, lasts: Duration) -> impl Effect + 'static {
+        move |elapsed: Duration, frame: &mut LedFrame| {
+            elapsed < lasts
+                && pads
+                    .iter()
+                    .all(|pad| frame.set_pad(LedMode::On100Percent, *pad, color).is_ok())
+        }
+    }
+
+    #[test]
+    fn frame_rate_is_at_least_one_frame_per_second() {
+        assert_eq!(frame_interval(0), Duration::from_secs(1));
+        assert_eq!(frame_interval(1), Duration::from_secs(1));
+        assert_eq!(frame_interval(50), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn effects_are_drawn_over_the_background_in_start_order() -> Result<(), Box<dyn Error>> {
+        let start = Instant::now();
+        let mut scene = Scene::new(30);
+        scene
+            .background
+            .fill_pads(LedMode::On50Percent, LedColor::Blue1);
+        scene.start(
+            Box::new(paint(&[0], LedColor::Red, Duration::from_secs(1))),
+            start,
+        );
+        scene.start(
+            Box::new(paint(&[0, 1], LedColor::Green, Duration::from_secs(1))),
+            start + Duration::from_millis(100),
+        );
+        let mut frame = LedFrame::default();
+        scene.render(start + Duration::from_millis(500), &mut frame);
+        assert_eq!(frame.pad(0)?, lit(LedMode::On100Percent, LedColor::Green));
+        assert_eq!(frame.pad(1)?, lit(LedMode::On100Percent, LedColor::Green));
+        assert_eq!(frame.pad(2)?, lit(LedMode::On50Percent, LedColor::Blue1));
+        Ok(())
+    }
+
+    #[test]
+    fn effects_that_are_over_are_dropped() -> Result<(), Box<dyn Error>> {
+        let start = Instant::now();
+        let mut scene = Scene::new(30);
+        let short = scene.start(
+            Box::new(paint(&[0], LedColor::Red, Duration::from_secs(1))),
+            start,
+        );
+        let long = scene.start(
+            Box::new(paint(&[1], LedColor::Red, Duration::from_secs(3))),
+            start,
+        );
+        let mut frame = LedFrame::default();
+        scene.render(start + Duration::from_secs(2), &mut frame);
+        assert_eq!(
+            scene
+                .effects
+                .iter()
+                .map(|playing| playing.id)
+                .collect::<Vec<_>>(),
+            vec![long]
+        );
+        assert_ne!(short, long);
+        assert_eq!(frame.pad(0)?, PadLed::default());
+        assert_eq!(frame.pad(1)?, lit(LedMode::On100Percent, LedColor::Red));
+        scene.render(start + Duration::from_secs(3), &mut frame);
+        assert!(scene.effects.is_empty());
+        assert_eq!(frame, scene.background);
+        Ok(())
+    }
+
+    #[test]
+    fn played_effects_run_until_stopped() {
+        let device = LoopbackDevice::new();
+        let animator = Animator::builder()
+            .output(Arc::new(Mutex::new(ChannelOutput::from_sink(
+                device.output(),
+            ))))
+            .build();
+        let hour = Duration::from_secs(3600);
+        let red = animator.play(paint(&[0], LedColor::Red, hour));
+        let green = animator.play(paint(&[1], LedColor::Green, hour));
+        let blue = animator.play(paint(&[2], LedColor::Blue1, hour));
+        assert!(animator.is_playing(red) && animator.is_playing(green));
+        animator.stop(red);
+        animator.stop(red);
+        assert!(!animator.is_playing(red));
+        assert!(animator.is_playing(green) && animator.is_playing(blue));
+        animator.stop_all();
+        assert!(!animator.is_playing(green) && !animator.is_playing(blue));
+    }
+
+    #[test]
+    fn background_updates_are_kept_by_the_scene() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let animator = Animator::builder()
+            .output(Arc::new(Mutex::new(ChannelOutput::from_sink(
+                device.output(),
+            ))))
+            .build();
+        animator.update_background(|frame| {
+            let _ = frame.set_pad(LedMode::On25Percent, PadKey::Grid(1, 1), LedColor::Red);
+        });
+        assert_eq!(
+            lock(&animator.scene).background.pad(PadKey::Grid(1, 1))?,
+            lit(LedMode::On25Percent, LedColor::Red)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ticks_send_only_the_lights_that_changed() {
+        let device = LoopbackDevice::new();
+        let scene = Arc::new(Mutex::new(Scene::new(50)));
+        let mut ticker = Ticker {
+            scene: Arc::clone(&scene),
+            running: Arc::new(AtomicBool::new(true)),
+            output: Arc::new(Mutex::new(ChannelOutput::from_sink(device.output()))),
+            frame_buffer: FrameBuffer::default(),
+        };
+        let start = Instant::now();
+        assert_eq!(ticker.tick(start), Duration::from_millis(20));
+        assert_eq!(
+            device.take_received().len(),
+            usize::from(PAD_COUNT) + LedButton::ALL.len()
+        );
+        ticker.tick(start + Duration::from_millis(20));
+        assert!(device.take_received().is_empty());
+
+        lock(&scene).start(
+            Box::new(paint(&[3], LedColor::Red, Duration::from_secs(1))),
+            start,
+        );
+        ticker.tick(start + Duration::from_millis(40));
+        assert_eq!(device.take_received(), vec![vec![0x96, 0x03, 0x05]]);
+        ticker.tick(start + Duration::from_millis(60));
+        assert!(device.take_received().is_empty());
+        ticker.tick(start + Duration::from_secs(1));
+        assert_eq!(device.take_received(), vec![vec![0x96, 0x03, 0x00]]);
+    }
+}
@@ -0,0 +1,528 @@
+use crate::enums::button::geometry::Origin;
+use crate::enums::button::pads::{PAD_COUNT, PadKey};
+use crate::enums::led_light::behavior::{Brightness, LedBehavior};
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::io::frame::{LedFrame, PadLed};
+use bon::{Builder, bon};
+use std::time::Duration;
+
+/// Something drawn by the `Animator` on every frame, on top of the background and of the effects started before it
+pub trait Effect: Send {
+    /// Paints the effect as it looks `elapsed` after it was started, returns false once it is over
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool;
+}
+
+impl<F> Effect for F
+where
+    F: FnMut(Duration, &mut LedFrame) -> bool + Send,
+{
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        self(elapsed, frame)
+    }
+}
+
+/// Notes outside the grid are skipped, effects never fail halfway through a frame
+fn paint<M: Into<LedMode>>(frame: &mut LedFrame, led_mode: M, note: u8, color: LedColor) {
+    if note < PAD_COUNT {
+        let _ = frame.set_pad(led_mode, note, color);
+    }
+}
+
+fn progress(elapsed: Duration, duration: Duration) -> f32 {
+    if duration.is_zero() {
+        1.0
+    } else {
+        (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+    }
+}
+
+/// Number of whole `step`s in `elapsed`
+fn steps(elapsed: Duration, step: Duration) -> usize {
+    (elapsed.as_nanos() / step.as_nanos().max(1)) as usize
+}
+
+/// Changes the colour of the pads from `from` to `to` over `duration`, through the palette entries in between
+#[derive(Debug, Clone)]
+pub struct Fade {
+    pads: Vec<u8>,
+    from: LedColor,
+    to: LedColor,
+    duration: Duration,
+    hold: bool,
+}
+
+#[bon]
+impl Fade {
+    /// With `hold`, the pads keep the `to` colour until the effect is stopped
+    #[builder]
+    pub fn new(
+        pads: Vec<u8>,
+        from: LedColor,
+        to: LedColor,
+        duration: Duration,
+        hold: Option<bool>,
+    ) -> Self {
+        Self {
+            pads,
+            from,
+            to,
+            duration,
+            hold: hold.unwrap_or(false),
+        }
+    }
+}
+
+impl Effect for Fade {
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        let t = progress(elapsed, self.duration);
+        let color = LedColor::nearest(self.from.rgb().lerp(self.to.rgb(), t));
+        for note in &self.pads {
+            paint(frame, LedMode::On100Percent, *note, color);
+        }
+        self.hold || elapsed < self.duration
+    }
+}
+
+/// Steps the pads through a list of colours, until stopped unless a number of `cycles` is given
+/// ```Rust
+///let cycle = ColorCycle::builder()
+///    .pads((0..8).collect())
+///    .colors(LedColor::rainbow(12, 1.0))
+///    .step(Duration::from_millis(80))
+///    .build();
+/// ```
+#[derive(Debug, Clone, Builder)]
+pub struct ColorCycle {
+    pads: Vec<u8>,
+    colors: Vec<LedColor>,
+    step: Duration,
+    cycles: Option<u32>,
+}
+
+impl Effect for ColorCycle {
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        let index = steps(elapsed, self.step);
+        let over = self
+            .cycles
+            .is_some_and(|cycles| index >= cycles as usize * self.colors.len());
+        if over || self.colors.is_empty() {
+            return false;
+        }
+        let color = self.colors[index % self.colors.len()];
+        for note in &self.pads {
+            paint(frame, LedMode::On100Percent, *note, color);
+        }
+        true
+    }
+}
+
+/// A light running along a path of pads, followed by a tail that gets dimmer
+#[derive(Debug, Clone)]
+pub struct Chase {
+    path: Vec<u8>,
+    color: LedColor,
+    step: Duration,
+    tail: usize,
+    loops: Option<u32>,
+}
+
+#[bon]
+impl Chase {
+    /// `tail` is the number of lit pads including the head, 3 by default. Runs until stopped unless a number of `loops` is given
+    #[builder]
+    pub fn new(
+        path: Vec<u8>,
+        color: LedColor,
+        step: Duration,
+        tail: Option<usize>,
+        loops: Option<u32>,
+    ) -> Self {
+        Self {
+            path,
+            color,
+            step,
+            tail: tail.unwrap_or(3).max(1),
+            loops,
+        }
+    }
+}
+
+impl Effect for Chase {
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        let head = steps(elapsed, self.step);
+        let over = self
+            .loops
+            .is_some_and(|loops| head >= loops as usize * self.path.len());
+        if over || self.path.is_empty() {
+            return false;
+        }
+        let mut brightness = Some(Brightness::Percent100);
+        for behind in 0..self.tail.min(self.path.len()).min(head + 1) {
+            let note = self.path[(head - behind) % self.path.len()];
+            let level = brightness.unwrap_or(Brightness::Percent10);
+            paint(frame, LedBehavior::solid(level), note, self.color);
+            brightness = level.dimmer().and_then(Brightness::dimmer);
+        }
+        true
+    }
+}
+
+/// A ring growing from a pad to the edges of the grid, dimming as it goes
+#[derive(Debug, Clone)]
+pub struct Ripple {
+    center: u8,
+    color: LedColor,
+    speed: f32,
+    width: f32,
+}
+
+#[bon]
+impl Ripple {
+    /// `speed` is in pads per second, 10 by default, `width` is the thickness of the ring in pads, 1 by default
+    /// ```Rust
+    ///device.listen(
+    ///    move |_, rx_data, _, animator: &mut Animator| {
+    ///        if let (PadsAndKnobsInputGroup::Pads(pad), true) = (rx_data.input_group, rx_data.is_pressed()) {
+    ///            animator.play(Ripple::builder().center(pad).color(LedColor::Cyan1).build());
+    ///        }
+    ///    },
+    ///    animator,
+    ///    PadsAndKnobsChannel,
+    /// )?;
+    /// ```
+    #[builder]
+    pub fn new(
+        #[builder(into)] center: u8,
+        color: LedColor,
+        speed: Option<f32>,
+        width: Option<f32>,
+    ) -> Self {
+        Self {
+            center,
+            color,
+            speed: speed.unwrap_or(10.0),
+            width: width.unwrap_or(1.0),
+        }
+    }
+}
+
+impl Effect for Ripple {
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        let radius = elapsed.as_secs_f32() * self.speed;
+        let farthest = (0..PAD_COUNT)
+            .map(|note| pad_distance(self.center, note))
+            .fold(0.0, f32::max);
+        if radius - self.width > farthest {
+            return false;
+        }
+        let fading = 1.0 - (radius / farthest.max(1.0)).min(1.0);
+        #[allow(clippy::cast_sign_loss)]
+        let brightness = Brightness::from_percent((fading * 100.0) as u8);
+        for note in 0..PAD_COUNT {
+            if (pad_distance(self.center, note) - radius).abs() * 2.0 <= self.width {
+                paint(frame, LedBehavior::solid(brightness), note, self.color);
+            }
+        }
+        true
+    }
+}
+
+fn pad_distance(from: u8, to: u8) -> f32 {
+    let position = |note: u8| {
+        PadKey::from_note(note).map_or((0.0, 0.0), |pad| {
+            let (x, y) = pad.to_xy(Origin::BottomLeft);
+            (f32::from(x), f32::from(y))
+        })
+    };
+    let ((from_x, from_y), (to_x, to_y)) = (position(from), position(to));
+    (to_x - from_x).hypot(to_y - from_y)
+}
+
+/// Pads shown for `hold`, before moving to the next keyframe of a `Sequence`
+#[derive(Debug, Clone, Default)]
+pub struct Keyframe {
+    hold: Duration,
+    pads: Vec<(u8, PadLed)>,
+}
+
+impl Keyframe {
+    #[must_use]
+    pub const fn new(hold: Duration) -> Self {
+        Self {
+            hold,
+            pads: Vec::new(),
+        }
+    }
+
+    /// Adds a pad to the keyframe, pads not listed show what is below the sequence
+    #[must_use]
+    pub fn with_pad<M, T>(mut self, led_mode: M, note: T, color: LedColor) -> Self
+    where
+        M: Into<LedMode>,
+        T: Into<u8>,
+    {
+        self.pads.push((
+            note.into(),
+            PadLed {
+                mode: led_mode.into(),
+                color,
+            },
+        ));
+        self
+    }
+}
+
+/// Keyframes played one after the other
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    keyframes: Vec<Keyframe>,
+    looping: bool,
+}
+
+#[bon]
+impl Sequence {
+    /// ```Rust
+    ///let blink_corners = Sequence::builder()
+    ///    .keyframes(vec![
+    ///        Keyframe::new(Duration::from_millis(250)).with_pad(LedMode::On100Percent, PadKey::Grid(1, 1), LedColor::Red),
+    ///        Keyframe::new(Duration::from_millis(250)).with_pad(LedMode::On100Percent, PadKey::Grid(5, 8), LedColor::Red),
+    ///    ])
+    ///    .looping(true)
+    ///    .build();
+    /// ```
+    #[builder]
+    pub fn new(keyframes: Vec<Keyframe>, looping: Option<bool>) -> Self {
+        Self {
+            keyframes,
+            looping: looping.unwrap_or(false),
+        }
+    }
+}
+
+impl Effect for Sequence {
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        let total: Duration = self.keyframes.iter().map(|k| k.hold).sum();
+        if total.is_zero() || (!self.looping && elapsed >= total) {
+            return false;
+        }
+        let mut position = if self.looping {
+            Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64)
+        } else {
+            elapsed
+        };
+        let keyframe = self.keyframes.iter().find(|keyframe| {
+            let current = position < keyframe.hold;
+            position = position.saturating_sub(keyframe.hold);
+            current
+        });
+        for (note, pad) in keyframe.map(|k| k.pads.as_slice()).unwrap_or_default() {
+            paint(frame, pad.mode, *note, pad.color);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chase, ColorCycle, Effect, Fade, Keyframe, Ripple, Sequence};
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::errors::mapping::HardwareMappingError;
+    use crate::io::frame::{LedFrame, PadLed};
+    use std::time::Duration;
+
+    const OFF: PadLed = PadLed {
+        mode: LedMode::On100Percent,
+        color: LedColor::Off,
+    };
+
+    const fn lit(mode: LedMode, color: LedColor) -> PadLed {
+        PadLed { mode, color }
+    }
+
+    /// Renders the effect on a blank frame at `millis`, returns whether it is still running and the frame
+    fn render_at(effect: &mut impl Effect, millis: u64) -> (bool, LedFrame) {
+        let mut frame = LedFrame::default();
+        let running = effect.render(Duration::from_millis(millis), &mut frame);
+        (running, frame)
+    }
+
+    #[test]
+    fn fade_goes_from_one_colour_to_the_other() -> Result<(), HardwareMappingError> {
+        let mut fade = Fade::builder()
+            .pads(vec![0, 39])
+            .from(LedColor::Off)
+            .to(LedColor::White)
+            .duration(Duration::from_secs(1))
+            .build();
+        let (running, frame) = render_at(&mut fade, 0);
+        assert!(running);
+        assert_eq!(frame.pad(39)?, OFF);
+        let (_, frame) = render_at(&mut fade, 500);
+        let grey = LedColor::nearest(RgbColor::new(128, 128, 128));
+        assert_eq!(frame.pad(0)?, lit(LedMode::On100Percent, grey));
+        assert_eq!(frame.pad(1)?, OFF);
+        let (running, frame) = render_at(&mut fade, 1000);
+        assert!(!running);
+        assert_eq!(frame.pad(39)?.color, LedColor::White);
+        Ok(())
+    }
+
+    #[test]
+    fn held_fade_keeps_running_on_the_last_colour() -> Result<(), HardwareMappingError> {
+        let mut fade = Fade::builder()
+            .pads(vec![5])
+            .from(LedColor::White)
+            .to(LedColor::Red)
+            .duration(Duration::from_millis(100))
+            .hold(true)
+            .build();
+        let (running, frame) = render_at(&mut fade, 10_000);
+        assert!(running);
+        assert_eq!(frame.pad(5)?.color, LedColor::Red);
+        Ok(())
+    }
+
+    #[test]
+    fn color_cycle_wraps_and_stops_after_its_cycles() -> Result<(), HardwareMappingError> {
+        let mut cycle = ColorCycle::builder()
+            .pads(vec![3])
+            .colors(vec![LedColor::Red, LedColor::Green, LedColor::Blue1])
+            .step(Duration::from_millis(100))
+            .cycles(2)
+            .build();
+        for (millis, color) in [
+            (0, LedColor::Red),
+            (150, LedColor::Green),
+            (299, LedColor::Blue1),
+            (300, LedColor::Red),
+            (599, LedColor::Blue1),
+        ] {
+            let (running, frame) = render_at(&mut cycle, millis);
+            assert!(running);
+            assert_eq!(frame.pad(3)?.color, color);
+        }
+        let (running, frame) = render_at(&mut cycle, 600);
+        assert!(!running);
+        assert_eq!(frame.pad(3)?, OFF);
+        let mut endless = ColorCycle::builder()
+            .pads(vec![3])
+            .colors(vec![LedColor::Red])
+            .step(Duration::from_millis(100))
+            .build();
+        assert!(render_at(&mut endless, 60_000).0);
+        Ok(())
+    }
+
+    #[test]
+    fn chase_tail_gets_dimmer_behind_the_head() -> Result<(), HardwareMappingError> {
+        let mut chase = Chase::builder()
+            .path((0..8).collect())
+            .color(LedColor::Red)
+            .step(Duration::from_millis(100))
+            .tail(4)
+            .loops(2)
+            .build();
+        let (_, frame) = render_at(&mut chase, 0);
+        assert_eq!(frame.pad(0)?, lit(LedMode::On100Percent, LedColor::Red));
+        assert_eq!(frame.pad(7)?, OFF);
+        let (running, frame) = render_at(&mut chase, 450);
+        assert!(running);
+        assert_eq!(frame.pad(4)?, lit(LedMode::On100Percent, LedColor::Red));
+        assert_eq!(frame.pad(3)?, lit(LedMode::On75Percent, LedColor::Red));
+        assert_eq!(frame.pad(2)?, lit(LedMode::On50Percent, LedColor::Red));
+        assert_eq!(frame.pad(1)?, lit(LedMode::On10Percent, LedColor::Red));
+        assert_eq!(frame.pad(0)?, OFF);
+        assert_eq!(frame.pad(5)?, OFF);
+        Ok(())
+    }
+
+    #[test]
+    fn chase_wraps_around_its_path_until_the_last_loop() -> Result<(), HardwareMappingError> {
+        let mut chase = Chase::builder()
+            .path(vec![10, 11, 12])
+            .color(LedColor::Green)
+            .step(Duration::from_millis(100))
+            .tail(2)
+            .loops(2)
+            .build();
+        let (running, frame) = render_at(&mut chase, 300);
+        assert!(running);
+        assert_eq!(frame.pad(10)?.mode, LedMode::On100Percent);
+        assert_eq!(frame.pad(12)?.mode, LedMode::On75Percent);
+        assert!(render_at(&mut chase, 599).0);
+        let (running, frame) = render_at(&mut chase, 600);
+        assert!(!running);
+        assert_eq!(frame, LedFrame::default());
+        Ok(())
+    }
+
+    #[test]
+    fn ripple_grows_from_its_center_and_ends_past_the_grid() -> Result<(), HardwareMappingError> {
+        let mut ripple = Ripple::builder()
+            .center(0)
+            .color(LedColor::Cyan1)
+            .speed(1.0)
+            .build();
+        let (running, frame) = render_at(&mut ripple, 0);
+        assert!(running);
+        assert_eq!(frame.pad(0)?, lit(LedMode::On100Percent, LedColor::Cyan1));
+        assert_eq!(frame.pad(1)?, OFF);
+        let (_, frame) = render_at(&mut ripple, 1000);
+        assert_eq!(frame.pad(0)?, OFF);
+        assert_eq!(frame.pad(1)?.color, LedColor::Cyan1);
+        assert_eq!(frame.pad(8)?.color, LedColor::Cyan1);
+        assert_ne!(frame.pad(1)?.mode, LedMode::On100Percent);
+        // The farthest pad is 7 pads right and 4 up from the bottom left corner
+        assert!(render_at(&mut ripple, 8_000).0);
+        assert!(!render_at(&mut ripple, 10_000).0);
+        Ok(())
+    }
+
+    fn two_keyframes(looping: bool) -> Sequence {
+        Sequence::builder()
+            .keyframes(vec![
+                Keyframe::new(Duration::from_millis(100)).with_pad(
+                    LedMode::On100Percent,
+                    0,
+                    LedColor::Red,
+                ),
+                Keyframe::new(Duration::from_millis(200)).with_pad(
+                    LedMode::Blinking1over4,
+                    1,
+                    LedColor::Green,
+                ),
+            ])
+            .looping(looping)
+            .build()
+    }
+
+    #[test]
+    fn sequence_shows_the_keyframe_of_the_moment() -> Result<(), HardwareMappingError> {
+        let mut sequence = two_keyframes(false);
+        let (_, frame) = render_at(&mut sequence, 99);
+        assert_eq!(frame.pad(0)?, lit(LedMode::On100Percent, LedColor::Red));
+        assert_eq!(frame.pad(1)?, OFF);
+        let (running, frame) = render_at(&mut sequence, 100);
+        assert!(running);
+        assert_eq!(frame.pad(0)?, OFF);
+        assert_eq!(frame.pad(1)?, lit(LedMode::Blinking1over4, LedColor::Green));
+        assert!(render_at(&mut sequence, 299).0);
+        assert!(!render_at(&mut sequence, 300).0);
+        Ok(())
+    }
+
+    #[test]
+    fn looping_sequence_wraps_around() -> Result<(), HardwareMappingError> {
+        let mut sequence = two_keyframes(true);
+        let (running, frame) = render_at(&mut sequence, 350);
+        assert!(running);
+        assert_eq!(frame.pad(0)?.color, LedColor::Red);
+        let (_, frame) = render_at(&mut sequence, 3_150);
+        assert_eq!(frame.pad(1)?.color, LedColor::Green);
+        assert!(!render_at(&mut Sequence::builder().keyframes(Vec::new()).build(), 0).0);
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod animator;
+pub mod effect;
@@ -0,0 +1,352 @@
+use crate::enums::button::pads::{GRID_COLUMNS, GRID_ROWS, PAD_COUNT, PadKey};
+use crate::errors::mapping::HardwareMappingError;
+
+/// Corner of the grid used as (0, 0) by the x/y coordinates
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// Same orientation as `PadKey::Grid` and screens
+    #[default]
+    TopLeft,
+    /// Same orientation as the note numbers, which start from the bottom row
+    BottomLeft,
+}
+
+/// Symmetries of the grid. A quarter turn does not fit an 8x5 grid on itself, so half turns are the only rotation
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Transform {
+    #[default]
+    Identity,
+    Rotate180,
+    /// Swaps left and right
+    MirrorHorizontal,
+    /// Swaps top and bottom
+    MirrorVertical,
+}
+
+impl PadKey {
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the row is not between 1 and `GRID_ROWS` or the column between 1 and `GRID_COLUMNS`
+    pub const fn new(row: u8, column: u8) -> Result<Self, HardwareMappingError> {
+        let pad = Self::Grid(row, column);
+        if pad.is_valid() {
+            Ok(pad)
+        } else {
+            Err(HardwareMappingError::InvalidPadPosition(row, column))
+        }
+    }
+
+    /// Whether the row and the column are inside the grid
+    #[must_use]
+    pub const fn is_valid(self) -> bool {
+        let Self::Grid(row, column) = self;
+        row >= 1 && row <= GRID_ROWS && column >= 1 && column <= GRID_COLUMNS
+    }
+
+    /// Pad sending the note, the inverse of `From<PadKey> for u8`
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub const fn from_note(note: u8) -> Result<Self, HardwareMappingError> {
+        if note < PAD_COUNT {
+            Ok(Self::Grid(
+                GRID_ROWS - note / GRID_COLUMNS,
+                note % GRID_COLUMNS + 1,
+            ))
+        } else {
+            Err(HardwareMappingError::InvalidPadIndex(note))
+        }
+    }
+
+    /// Note sent by the pad, same as `From<PadKey> for u8` for the pads of the grid
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the pad is outside the grid
+    pub const fn note(self) -> Result<u8, HardwareMappingError> {
+        let Self::Grid(row, column) = self;
+        if self.is_valid() {
+            Ok((GRID_ROWS - row) * GRID_COLUMNS + (column - 1))
+        } else {
+            Err(HardwareMappingError::InvalidPadPosition(row, column))
+        }
+    }
+
+    /// Pad at the zero based coordinates, x grows to the right and y away from the origin
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the coordinates are outside the grid
+    pub const fn from_xy(x: u8, y: u8, origin: Origin) -> Result<Self, HardwareMappingError> {
+        if x >= GRID_COLUMNS || y >= GRID_ROWS {
+            return Err(HardwareMappingError::InvalidPadCoordinates { x, y });
+        }
+        let row = match origin {
+            Origin::TopLeft => y + 1,
+            Origin::BottomLeft => GRID_ROWS - y,
+        };
+        Ok(Self::Grid(row, x + 1))
+    }
+
+    /// Zero based (x, y) coordinates of the pad, the inverse of `from_xy`.
+    /// A pad outside the grid gives coordinates outside it too
+    #[must_use]
+    pub const fn to_xy(self, origin: Origin) -> (u8, u8) {
+        let Self::Grid(row, column) = self;
+        let y = match origin {
+            Origin::TopLeft => row.wrapping_sub(1),
+            Origin::BottomLeft => GRID_ROWS.wrapping_sub(row),
+        };
+        (column.wrapping_sub(1), y)
+    }
+
+    /// Every pad, row by row from the top left one
+    pub fn all() -> impl Iterator<Item = Self> {
+        (1..=GRID_ROWS).flat_map(Self::row)
+    }
+
+    /// Pads of the row from left to right, nothing if the row is outside the grid
+    pub fn row(row: u8) -> impl Iterator<Item = Self> {
+        let valid = (1..=GRID_ROWS).contains(&row);
+        (1..=GRID_COLUMNS)
+            .filter(move |_| valid)
+            .map(move |column| Self::Grid(row, column))
+    }
+
+    /// Pads of the column from top to bottom, nothing if the column is outside the grid
+    pub fn column(column: u8) -> impl Iterator<Item = Self> {
+        let valid = (1..=GRID_COLUMNS).contains(&column);
+        (1..=GRID_ROWS)
+            .filter(move |_| valid)
+            .map(move |row| Self::Grid(row, column))
+    }
+
+    /// Pad moved by the given number of rows (down) and columns (right), `None` if it leaves the grid
+    #[must_use]
+    pub fn offset(self, rows: i8, columns: i8) -> Option<Self> {
+        let Self::Grid(row, column) = self;
+        let pad = Self::Grid(
+            row.checked_add_signed(rows)?,
+            column.checked_add_signed(columns)?,
+        );
+        pad.is_valid().then_some(pad)
+    }
+
+    /// Up to 8 surrounding pads, diagonals included
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|rows| (-1..=1).map(move |columns| (rows, columns)))
+            .filter(|offset| *offset != (0, 0))
+            .filter_map(move |(rows, columns)| self.offset(rows, columns))
+    }
+
+    /// Up to 4 pads above, below, left and right
+    pub fn orthogonal_neighbours(self) -> impl Iterator<Item = Self> {
+        [(-1, 0), (0, -1), (0, 1), (1, 0)]
+            .into_iter()
+            .filter_map(move |(rows, columns)| self.offset(rows, columns))
+    }
+
+    /// Pads of the rectangle with the two pads as opposite corners, row by row from the top left one
+    pub fn rectangle(corner: Self, opposite: Self) -> impl Iterator<Item = Self> {
+        let (Self::Grid(row_a, column_a), Self::Grid(row_b, column_b)) = (corner, opposite);
+        let columns = column_a.min(column_b)..=column_a.max(column_b);
+        (row_a.min(row_b)..=row_a.max(row_b))
+            .flat_map(move |row| columns.clone().map(move |column| Self::Grid(row, column)))
+            .filter(|pad| pad.is_valid())
+    }
+
+    /// Pads on the straight line between the two pads, both included, from `from` to `to`.
+    /// Points of the line outside the grid are skipped
+    #[must_use]
+    pub fn line(from: Self, to: Self) -> Vec<Self> {
+        let (Self::Grid(row, column), Self::Grid(to_row, to_column)) = (from, to);
+        let (mut x, mut y) = (i16::from(column), i16::from(row));
+        let (to_x, to_y) = (i16::from(to_column), i16::from(to_row));
+        let (dx, dy) = ((to_x - x).abs(), -(to_y - y).abs());
+        let (step_x, step_y) = ((to_x - x).signum(), (to_y - y).signum());
+        let mut error = dx + dy;
+        let mut pads = Vec::new();
+        loop {
+            if let (Ok(row), Ok(column)) = (u8::try_from(y), u8::try_from(x)) {
+                let pad = Self::Grid(row, column);
+                if pad.is_valid() {
+                    pads.push(pad);
+                }
+            }
+            if (x, y) == (to_x, to_y) {
+                return pads;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn transform(self, transform: Transform) -> Self {
+        let Self::Grid(row, column) = self;
+        let (mirrored_row, mirrored_column) = (
+            (GRID_ROWS + 1).saturating_sub(row),
+            (GRID_COLUMNS + 1).saturating_sub(column),
+        );
+        match transform {
+            Transform::Identity => self,
+            Transform::Rotate180 => Self::Grid(mirrored_row, mirrored_column),
+            Transform::MirrorHorizontal => Self::Grid(row, mirrored_column),
+            Transform::MirrorVertical => Self::Grid(mirrored_row, column),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Origin, Transform};
+    use crate::enums::button::pads::{OUT_OF_GRID_NOTE, PAD_COUNT, PadData, PadKey};
+    use crate::errors::mapping::HardwareMappingError;
+    use std::error::Error;
+
+    #[test]
+    fn notes_and_pads_round_trip() -> Result<(), Box<dyn Error>> {
+        for note in 0..PAD_COUNT {
+            let pad = PadKey::from_note(note)?;
+            assert!(pad.is_valid());
+            assert_eq!(pad.note()?, note);
+            assert_eq!(u8::from(pad), note);
+        }
+        assert_eq!(PadKey::from_note(0)?, PadKey::Grid(5, 1));
+        assert_eq!(PadKey::from_note(39)?, PadKey::Grid(1, 8));
+        assert!(PadKey::from_note(PAD_COUNT).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn pads_outside_the_grid_have_no_note() {
+        for pad in [
+            PadKey::Grid(0, 1),
+            PadKey::Grid(6, 1),
+            PadKey::Grid(1, 0),
+            PadKey::Grid(1, 9),
+        ] {
+            let PadKey::Grid(row, column) = pad;
+            assert!(matches!(
+                pad.note(),
+                Err(HardwareMappingError::InvalidPadPosition(r, c)) if (r, c) == (row, column)
+            ));
+            assert_eq!(u8::from(pad), OUT_OF_GRID_NOTE);
+            for origin in [Origin::TopLeft, Origin::BottomLeft] {
+                let (x, y) = pad.to_xy(origin);
+                assert!(PadKey::from_xy(x, y, origin).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn coordinates_round_trip_from_both_origins() -> Result<(), Box<dyn Error>> {
+        for pad in PadKey::all() {
+            for origin in [Origin::TopLeft, Origin::BottomLeft] {
+                let (x, y) = pad.to_xy(origin);
+                assert_eq!(PadKey::from_xy(x, y, origin)?, pad);
+            }
+        }
+        assert_eq!(
+            PadKey::from_xy(0, 0, Origin::BottomLeft)?,
+            PadKey::Grid(5, 1)
+        );
+        assert!(matches!(
+            PadKey::from_xy(8, 2, Origin::TopLeft),
+            Err(HardwareMappingError::InvalidPadCoordinates { x: 8, y: 2 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rows_columns_and_neighbours_stay_inside_the_grid() {
+        assert_eq!(PadKey::all().count(), usize::from(PAD_COUNT));
+        assert_eq!(PadKey::row(6).count(), 0);
+        assert_eq!(PadKey::column(3).count(), 5);
+        assert_eq!(PadKey::Grid(1, 1).neighbours().count(), 3);
+        assert_eq!(PadKey::Grid(3, 4).neighbours().count(), 8);
+        assert_eq!(PadKey::Grid(5, 8).orthogonal_neighbours().count(), 2);
+        assert_eq!(PadKey::Grid(1, 1).offset(-1, 0), None);
+        assert_eq!(PadKey::Grid(1, 1).offset(4, 7), Some(PadKey::Grid(5, 8)));
+        assert_eq!(
+            PadKey::rectangle(PadKey::Grid(2, 3), PadKey::Grid(1, 2)).collect::<Vec<_>>(),
+            vec![
+                PadKey::Grid(1, 2),
+                PadKey::Grid(1, 3),
+                PadKey::Grid(2, 2),
+                PadKey::Grid(2, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_join_both_ends() {
+        assert_eq!(
+            PadKey::line(PadKey::Grid(1, 1), PadKey::Grid(3, 8)),
+            vec![
+                PadKey::Grid(1, 1),
+                PadKey::Grid(1, 2),
+                PadKey::Grid(2, 3),
+                PadKey::Grid(2, 4),
+                PadKey::Grid(2, 5),
+                PadKey::Grid(2, 6),
+                PadKey::Grid(3, 7),
+                PadKey::Grid(3, 8)
+            ]
+        );
+        assert_eq!(
+            PadKey::line(PadKey::Grid(4, 2), PadKey::Grid(4, 2)),
+            vec![PadKey::Grid(4, 2)]
+        );
+    }
+
+    #[test]
+    fn lines_skip_points_outside_the_grid() {
+        assert_eq!(
+            PadKey::line(PadKey::Grid(3, 7), PadKey::Grid(5, 11)),
+            vec![PadKey::Grid(3, 7), PadKey::Grid(4, 8)]
+        );
+        assert_eq!(
+            PadKey::line(PadKey::Grid(0, 2), PadKey::Grid(2, 2)),
+            vec![PadKey::Grid(1, 2), PadKey::Grid(2, 2)]
+        );
+    }
+
+    #[test]
+    fn transforms_are_their_own_inverse() {
+        for pad in PadKey::all() {
+            for transform in [
+                Transform::Identity,
+                Transform::Rotate180,
+                Transform::MirrorHorizontal,
+                Transform::MirrorVertical,
+            ] {
+                assert_eq!(pad.transform(transform).transform(transform), pad);
+            }
+        }
+        assert_eq!(
+            PadKey::Grid(1, 1).transform(Transform::Rotate180),
+            PadKey::Grid(5, 8)
+        );
+    }
+
+    #[test]
+    fn pressed_pads_decode_to_their_note() -> Result<(), Box<dyn Error>> {
+        for note in 0..PAD_COUNT {
+            let pad = PadKey::try_from(note)?;
+            assert_eq!(
+                pad,
+                PadKey::try_from(PadData::builder().index(note).build())?
+            );
+            assert_eq!(pad.note()?, note);
+        }
+        assert!(PadKey::try_from(PAD_COUNT).is_err());
+        Ok(())
+    }
+}
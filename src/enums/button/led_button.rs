@@ -0,0 +1,134 @@
+use crate::enums::button::knob_ctrl::KnobCtrlKey;
+use crate::enums::button::soft_keys::SoftKey;
+use crate::enums::input_group::PadsAndKnobsInputGroup;
+use crate::errors::mapping::HardwareMappingError;
+
+/// Buttons with a single colour LED: arrows and knob controls below the pads, soft keys on their right.
+/// Shift, Stop All Clips and the transport buttons have no LED
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LedButton {
+    Up,
+    Down,
+    Left,
+    Right,
+    KnobCtrl(KnobCtrlKey),
+    SoftKey(SoftKey),
+}
+
+impl LedButton {
+    pub const ALL: [Self; 13] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::KnobCtrl(KnobCtrlKey::Volume),
+        Self::KnobCtrl(KnobCtrlKey::Pan),
+        Self::KnobCtrl(KnobCtrlKey::Send),
+        Self::KnobCtrl(KnobCtrlKey::Device),
+        Self::SoftKey(SoftKey::ClipStop),
+        Self::SoftKey(SoftKey::Solo),
+        Self::SoftKey(SoftKey::Mute),
+        Self::SoftKey(SoftKey::RecArm),
+        Self::SoftKey(SoftKey::Select),
+    ];
+
+    /// Position of the button in `ALL`
+    #[must_use]
+    pub const fn slot(self) -> usize {
+        match self {
+            Self::Up => 0,
+            Self::Down => 1,
+            Self::Left => 2,
+            Self::Right => 3,
+            Self::KnobCtrl(k) => k as usize - KnobCtrlKey::Volume as usize + 4,
+            Self::SoftKey(k) => k as usize - SoftKey::ClipStop as usize + 8,
+        }
+    }
+}
+
+impl From<KnobCtrlKey> for LedButton {
+    fn from(key: KnobCtrlKey) -> Self {
+        Self::KnobCtrl(key)
+    }
+}
+
+impl From<SoftKey> for LedButton {
+    fn from(key: SoftKey) -> Self {
+        Self::SoftKey(key)
+    }
+}
+
+impl TryFrom<PadsAndKnobsInputGroup> for LedButton {
+    type Error = HardwareMappingError;
+    fn try_from(value: PadsAndKnobsInputGroup) -> Result<Self, Self::Error> {
+        match value {
+            PadsAndKnobsInputGroup::Up => Ok(Self::Up),
+            PadsAndKnobsInputGroup::Down => Ok(Self::Down),
+            PadsAndKnobsInputGroup::Left => Ok(Self::Left),
+            PadsAndKnobsInputGroup::Right => Ok(Self::Right),
+            PadsAndKnobsInputGroup::KnobCtrl(k) => Ok(Self::KnobCtrl(k)),
+            PadsAndKnobsInputGroup::SoftKeys(k) => Ok(Self::SoftKey(k)),
+            _ => Err(HardwareMappingError::InvalidButtonLedIndex(value.into())),
+        }
+    }
+}
+
+impl TryFrom<u8> for LedButton {
+    type Error = HardwareMappingError;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        PadsAndKnobsInputGroup::try_from(value)
+            .map_err(|_| HardwareMappingError::InvalidButtonLedIndex(value))?
+            .try_into()
+    }
+}
+
+impl From<LedButton> for PadsAndKnobsInputGroup {
+    fn from(button: LedButton) -> Self {
+        match button {
+            LedButton::Up => Self::Up,
+            LedButton::Down => Self::Down,
+            LedButton::Left => Self::Left,
+            LedButton::Right => Self::Right,
+            LedButton::KnobCtrl(k) => Self::KnobCtrl(k),
+            LedButton::SoftKey(k) => Self::SoftKeys(k),
+        }
+    }
+}
+
+impl From<LedButton> for u8 {
+    fn from(button: LedButton) -> Self {
+        PadsAndKnobsInputGroup::from(button).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LedButton;
+    use crate::errors::mapping::HardwareMappingError;
+    use std::error::Error;
+
+    #[test]
+    fn every_button_has_its_own_slot() {
+        for button in LedButton::ALL {
+            assert_eq!(LedButton::ALL[button.slot()], button);
+        }
+    }
+
+    #[test]
+    fn notes_round_trip_to_led_buttons() -> Result<(), Box<dyn Error>> {
+        for button in LedButton::ALL {
+            assert_eq!(LedButton::try_from(u8::from(button))?, button);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn buttons_without_led_are_rejected() {
+        for note in [81, 98, 91, 0, 48] {
+            assert!(matches!(
+                LedButton::try_from(note),
+                Err(HardwareMappingError::InvalidButtonLedIndex(n)) if n == note
+            ));
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod geometry;
 pub mod knob_ctrl;
+pub mod led_button;
 pub mod pads;
 pub mod soft_keys;
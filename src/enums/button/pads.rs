@@ -1,7 +1,16 @@
 use crate::errors::mapping::HardwareMappingError;
 use bon::Builder;
 
-#[derive(Debug, Copy, Clone)]
+/// Number of pads in the grid, their notes go from 0 to `PAD_COUNT` - 1
+pub const PAD_COUNT: u8 = 40;
+/// Rows of the grid, `PadKey::Grid` numbers them from 1 at the top
+pub const GRID_ROWS: u8 = 5;
+/// Columns of the grid, `PadKey::Grid` numbers them from 1 on the left
+pub const GRID_COLUMNS: u8 = 8;
+/// Note given by `From<PadKey> for u8` to the pads outside the grid, no pad or button of the device sends it
+pub const OUT_OF_GRID_NOTE: u8 = 0x7F;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum PadKey {
     Grid(u8, u8),
 }
@@ -24,25 +33,23 @@ impl PadKey {
     #[must_use]
     pub const fn get_col(self) -> u8 {
         match self {
-            Self::Grid(row, _) => row,
+            Self::Grid(_, col) => col,
         }
     }
+    /// Note sent by the pad, `OUT_OF_GRID_NOTE` for the pads outside the grid. `note` returns an error instead
     #[must_use]
-    pub fn get_index(self) -> u8 {
-        self.into()
+    pub const fn get_index(self) -> u8 {
+        match self.note() {
+            Ok(note) => note,
+            Err(_) => OUT_OF_GRID_NOTE,
+        }
     }
 }
 
+/// Same as `PadKey::get_index`, pads outside the grid give `OUT_OF_GRID_NOTE`
 impl From<&PadKey> for u8 {
     fn from(key: &PadKey) -> Self {
-        match key {
-            PadKey::Grid(row, col) => {
-                const ROWS: u8 = 5;
-                const COLS: u8 = 8;
-
-                (ROWS - row) * COLS + (col - 1)
-            }
-        }
+        key.get_index()
     }
 }
 
@@ -55,8 +62,8 @@ impl From<PadKey> for u8 {
 impl TryFrom<PadData> for PadKey {
     type Error = HardwareMappingError;
     fn try_from(data: PadData) -> Result<Self, Self::Error> {
-        let rows = data.n_of_rows.unwrap_or(5);
-        let cols = data.n_of_columns.unwrap_or(8);
+        let rows = data.n_of_rows.unwrap_or(GRID_ROWS);
+        let cols = data.n_of_columns.unwrap_or(GRID_COLUMNS);
 
         if data.index < rows * cols {
             let row = rows - (data.index / cols);
@@ -71,11 +78,6 @@ impl TryFrom<PadData> for PadKey {
 impl TryFrom<u8> for PadKey {
     type Error = HardwareMappingError;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        PadData::builder()
-            .index(value)
-            .n_of_rows(5)
-            .n_of_columns(8)
-            .build()
-            .try_into()
+        Self::from_note(value)
     }
 }
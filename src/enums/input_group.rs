@@ -2,11 +2,19 @@ use crate::enums::button::knob_ctrl::KnobCtrlKey;
 use crate::enums::button::pads::PadKey;
 use crate::enums::button::soft_keys::SoftKey;
 use crate::errors::mapping::HardwareMappingError;
-use crate::io::input_data::MidiInputData;
-use midi_msg::{ChannelVoiceMsg, MidiMsg};
+use crate::io::input_data::{InputAction, MidiInputData};
+use midi_msg::{Channel, ChannelVoiceMsg, MidiMsg};
 
+/// MIDI channel used by pads, buttons and knobs, the keyboard sends on the other ones
+pub const PADS_AND_KNOBS_MIDI_CHANNEL: Channel = Channel::Ch1;
+
+#[derive(Debug, Copy, Clone, Default)]
 pub struct PadsAndKnobsChannel;
+#[derive(Debug, Copy, Clone, Default)]
 pub struct KeyboardChannel;
+/// Pads, buttons, knobs and keyboard from a single connection, told apart by their MIDI channel
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CombinedChannel;
 
 pub trait ChannelKind {
     type Group;
@@ -18,73 +26,102 @@ impl ChannelKind for PadsAndKnobsChannel {
     type Group = PadsAndKnobsInputGroup;
 
     fn decode(msg: &MidiMsg) -> Option<MidiInputData<Self::Group>> {
-        match msg {
-            MidiMsg::ChannelVoice {
-                channel,
-                msg: ChannelVoiceMsg::NoteOn { note, velocity: _ },
-            } => PadsAndKnobsInputGroup::try_from(*note)
-                .map(|input_group| MidiInputData {
-                    channel: *channel,
-                    input_group,
-                    value: 1u8,
-                })
-                .ok(),
-            MidiMsg::ChannelVoice {
-                channel,
-                msg: ChannelVoiceMsg::NoteOff { note, velocity: _ },
-            } => PadsAndKnobsInputGroup::try_from(*note)
-                .map(|input_group| MidiInputData {
-                    channel: *channel,
-                    input_group,
-                    value: 0u8,
-                })
-                .ok(),
-            MidiMsg::ChannelVoice {
-                channel,
-                msg: ChannelVoiceMsg::ControlChange { control },
-            } => PadsAndKnobsInputGroup::try_from(control.control())
-                .map(|input_group| MidiInputData {
-                    channel: *channel,
-                    input_group,
+        let MidiMsg::ChannelVoice { channel, msg } = msg else {
+            return None;
+        };
+        let (index, action) = match msg {
+            ChannelVoiceMsg::ControlChange { control } => (
+                control.control(),
+                InputAction::Changed {
                     value: control.value(),
-                })
-                .ok(),
-            _ => None,
-        }
+                },
+            ),
+            _ => decode_note(*msg)?,
+        };
+        PadsAndKnobsInputGroup::try_from(index)
+            .map(|input_group| MidiInputData {
+                channel: *channel,
+                input_group,
+                action,
+            })
+            .ok()
     }
 }
 
 impl ChannelKind for KeyboardChannel {
     type Group = KeyboardInputGroup;
 
+    fn decode(msg: &MidiMsg) -> Option<MidiInputData<Self::Group>> {
+        let MidiMsg::ChannelVoice { channel, msg } = msg else {
+            return None;
+        };
+        let (note, action) = decode_note(*msg)?;
+        KeyboardInputGroup::try_from(note)
+            .map(|input_group| MidiInputData {
+                channel: *channel,
+                input_group,
+                action,
+            })
+            .ok()
+    }
+}
+
+/// Note on with velocity 0 is a release, as per MIDI convention
+const fn decode_note(msg: ChannelVoiceMsg) -> Option<(u8, InputAction)> {
+    match msg {
+        ChannelVoiceMsg::NoteOn { note, velocity: 0 } => {
+            Some((note, InputAction::Released { velocity: 0 }))
+        }
+        ChannelVoiceMsg::NoteOn { note, velocity } => {
+            Some((note, InputAction::Pressed { velocity }))
+        }
+        ChannelVoiceMsg::NoteOff { note, velocity } => {
+            Some((note, InputAction::Released { velocity }))
+        }
+        _ => None,
+    }
+}
+
+impl ChannelKind for CombinedChannel {
+    type Group = CombinedInputGroup;
+
     fn decode(msg: &MidiMsg) -> Option<MidiInputData<Self::Group>> {
         match msg {
-            MidiMsg::ChannelVoice {
-                channel,
-                msg: ChannelVoiceMsg::NoteOn { note, velocity: _ },
-            } => KeyboardInputGroup::try_from(*note)
-                .map(|input_group| MidiInputData {
-                    channel: *channel,
-                    input_group,
-                    value: 1u8,
-                })
-                .ok(),
-            MidiMsg::ChannelVoice {
-                channel,
-                msg: ChannelVoiceMsg::NoteOff { note, velocity: _ },
-            } => KeyboardInputGroup::try_from(*note)
-                .map(|input_group| MidiInputData {
-                    channel: *channel,
-                    input_group,
-                    value: 0u8,
+            MidiMsg::ChannelVoice { channel, .. } if *channel == PADS_AND_KNOBS_MIDI_CHANNEL => {
+                PadsAndKnobsChannel::decode(msg).map(|input| MidiInputData {
+                    channel: input.channel,
+                    input_group: CombinedInputGroup::PadsAndKnobs(input.input_group),
+                    action: input.action,
                 })
-                .ok(),
-            _ => None,
+            }
+            _ => KeyboardChannel::decode(msg).map(|input| MidiInputData {
+                channel: input.channel,
+                input_group: CombinedInputGroup::Keyboard(input.input_group),
+                action: input.action,
+            }),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CombinedInputGroup {
+    PadsAndKnobs(PadsAndKnobsInputGroup),
+    Keyboard(KeyboardInputGroup),
+}
+
+impl From<PadsAndKnobsInputGroup> for CombinedInputGroup {
+    fn from(value: PadsAndKnobsInputGroup) -> Self {
+        Self::PadsAndKnobs(value)
+    }
+}
+
+impl From<KeyboardInputGroup> for CombinedInputGroup {
+    fn from(value: KeyboardInputGroup) -> Self {
+        Self::Keyboard(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PadsAndKnobsInputGroup {
     Pads(PadKey),
     SoftKeys(SoftKey),
@@ -149,7 +186,7 @@ impl TryFrom<u8> for PadsAndKnobsInputGroup {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum KeyboardInputGroup {
     Key(u8),
 }
@@ -171,3 +208,47 @@ impl TryFrom<u8> for KeyboardInputGroup {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChannelKind, CombinedChannel, CombinedInputGroup, KeyboardInputGroup,
+        PadsAndKnobsInputGroup,
+    };
+    use crate::io::input_data::{InputAction, MidiInputData};
+    use midi_msg::{Channel, MidiMsg};
+    use std::error::Error;
+
+    fn decode(bytes: &[u8]) -> Result<Option<MidiInputData<CombinedInputGroup>>, Box<dyn Error>> {
+        Ok(CombinedChannel::decode(&MidiMsg::from_midi(bytes)?.0))
+    }
+
+    #[test]
+    fn first_channel_notes_and_controls_48_to_55_are_knobs() -> Result<(), Box<dyn Error>> {
+        for note in 48..=55 {
+            let knob = CombinedInputGroup::PadsAndKnobs(PadsAndKnobsInputGroup::Knob(note - 47));
+            let pressed = decode(&[0x90, note, 0x7F])?.ok_or("note not decoded")?;
+            assert_eq!(pressed.input_group, knob);
+            assert_eq!(pressed.channel, Channel::Ch1);
+            let turned = decode(&[0xB0, note, 0x01])?.ok_or("control not decoded")?;
+            assert_eq!(turned.input_group, knob);
+            assert_eq!(turned.action, InputAction::Changed { value: 1 });
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn same_notes_on_the_second_channel_are_keys() -> Result<(), Box<dyn Error>> {
+        for note in 48..=55 {
+            let key = decode(&[0x91, note, 0x40])?.ok_or("note not decoded")?;
+            assert_eq!(
+                key.input_group,
+                CombinedInputGroup::Keyboard(KeyboardInputGroup::Key(note - 47))
+            );
+            assert_eq!(key.channel, Channel::Ch2);
+            assert_eq!(key.action, InputAction::Pressed { velocity: 0x40 });
+        }
+        assert!(decode(&[0xB1, 48, 0x01])?.is_none());
+        Ok(())
+    }
+}
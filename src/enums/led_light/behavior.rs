@@ -0,0 +1,313 @@
+use crate::enums::led_light::mode::LedMode;
+use crate::errors::led::LedError;
+use midi_msg::Channel;
+
+/// Brightness levels of a solid pad, from the dimmest to the brightest
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Brightness {
+    Percent10,
+    Percent25,
+    Percent50,
+    Percent65,
+    Percent75,
+    Percent90,
+    #[default]
+    Percent100,
+}
+
+impl Brightness {
+    pub const ALL: [Self; 7] = [
+        Self::Percent10,
+        Self::Percent25,
+        Self::Percent50,
+        Self::Percent65,
+        Self::Percent75,
+        Self::Percent90,
+        Self::Percent100,
+    ];
+
+    #[must_use]
+    pub const fn percent(self) -> u8 {
+        match self {
+            Self::Percent10 => 10,
+            Self::Percent25 => 25,
+            Self::Percent50 => 50,
+            Self::Percent65 => 65,
+            Self::Percent75 => 75,
+            Self::Percent90 => 90,
+            Self::Percent100 => 100,
+        }
+    }
+
+    /// Next level up, `None` at full brightness
+    #[must_use]
+    pub const fn brighter(self) -> Option<Self> {
+        match self {
+            Self::Percent10 => Some(Self::Percent25),
+            Self::Percent25 => Some(Self::Percent50),
+            Self::Percent50 => Some(Self::Percent65),
+            Self::Percent65 => Some(Self::Percent75),
+            Self::Percent75 => Some(Self::Percent90),
+            Self::Percent90 => Some(Self::Percent100),
+            Self::Percent100 => None,
+        }
+    }
+
+    /// Next level down, `None` at the dimmest level
+    #[must_use]
+    pub const fn dimmer(self) -> Option<Self> {
+        match self {
+            Self::Percent10 => None,
+            Self::Percent25 => Some(Self::Percent10),
+            Self::Percent50 => Some(Self::Percent25),
+            Self::Percent65 => Some(Self::Percent50),
+            Self::Percent75 => Some(Self::Percent65),
+            Self::Percent90 => Some(Self::Percent75),
+            Self::Percent100 => Some(Self::Percent90),
+        }
+    }
+
+    /// Closest level to a percentage, values above 100 are full brightness
+    #[must_use]
+    pub fn from_percent(percent: u8) -> Self {
+        Self::ALL
+            .into_iter()
+            .min_by_key(|level| level.percent().abs_diff(percent))
+            .unwrap_or_default()
+    }
+}
+
+/// Period of a pulse or a blink, in fractions of a beat of the MIDI clock
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LedRate {
+    OneOver24,
+    OneOver16,
+    OneOver8,
+    OneOver4,
+    OneOver2,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum LedAnimation {
+    #[default]
+    Solid,
+    Pulse(LedRate),
+    Blink(LedRate),
+}
+
+/// Structured form of `LedMode`: how bright a pad is and whether it stays on, pulses or blinks.
+///
+/// The hardware only pulses and blinks at full brightness and has no 1/24 pulse, so the constructors reject those combinations
+/// ```Rust
+///let dim = LedBehavior::solid(Brightness::Percent50);
+///midi_out.set_pad_led(dim, PadKey::Grid(1, 1), LedColor::Red)?;
+///if let Some(brighter) = dim.brightness().brighter() {
+///    midi_out.set_pad_led(LedBehavior::solid(brighter), PadKey::Grid(1, 1), LedColor::Red)?;
+///}
+///midi_out.set_pad_led(LedBehavior::blink(LedRate::OneOver4), PadKey::Grid(1, 2), LedColor::Red)?;
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LedBehavior {
+    brightness: Brightness,
+    animation: LedAnimation,
+}
+
+impl LedBehavior {
+    /// # Errors
+    ///
+    /// Will return `LedError` if the device cannot show this brightness with this animation
+    pub const fn new(brightness: Brightness, animation: LedAnimation) -> Result<Self, LedError> {
+        let behavior = Self {
+            brightness,
+            animation,
+        };
+        if Self::is_supported(brightness, animation) {
+            Ok(behavior)
+        } else {
+            Err(LedError::UnsupportedBehavior(behavior))
+        }
+    }
+
+    #[must_use]
+    pub const fn solid(brightness: Brightness) -> Self {
+        Self {
+            brightness,
+            animation: LedAnimation::Solid,
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return `LedError` for `LedRate::OneOver24`, the fastest rate is only available when blinking
+    pub const fn pulse(rate: LedRate) -> Result<Self, LedError> {
+        Self::new(Brightness::Percent100, LedAnimation::Pulse(rate))
+    }
+
+    #[must_use]
+    pub const fn blink(rate: LedRate) -> Self {
+        Self {
+            brightness: Brightness::Percent100,
+            animation: LedAnimation::Blink(rate),
+        }
+    }
+
+    /// `pulse` without the check, for the rates known to be supported
+    const fn pulsing(rate: LedRate) -> Self {
+        Self {
+            brightness: Brightness::Percent100,
+            animation: LedAnimation::Pulse(rate),
+        }
+    }
+
+    /// Whether the device has a mode for this combination
+    #[must_use]
+    pub const fn is_supported(brightness: Brightness, animation: LedAnimation) -> bool {
+        match animation {
+            LedAnimation::Solid => true,
+            LedAnimation::Pulse(LedRate::OneOver24) => false,
+            LedAnimation::Pulse(_) | LedAnimation::Blink(_) => {
+                matches!(brightness, Brightness::Percent100)
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn brightness(self) -> Brightness {
+        self.brightness
+    }
+
+    #[must_use]
+    pub const fn animation(self) -> LedAnimation {
+        self.animation
+    }
+
+    /// MIDI channel the pad message is sent on, the status byte is `0x90` plus its index
+    #[must_use]
+    pub fn channel(self) -> Channel {
+        Channel::from_u8(u8::from(LedMode::from(self)) & 0x0F)
+    }
+}
+
+impl From<LedBehavior> for LedMode {
+    fn from(behavior: LedBehavior) -> Self {
+        match behavior.animation {
+            LedAnimation::Solid => match behavior.brightness {
+                Brightness::Percent10 => Self::On10Percent,
+                Brightness::Percent25 => Self::On25Percent,
+                Brightness::Percent50 => Self::On50Percent,
+                Brightness::Percent65 => Self::On65Percent,
+                Brightness::Percent75 => Self::On75Percent,
+                Brightness::Percent90 => Self::On90Percent,
+                Brightness::Percent100 => Self::On100Percent,
+            },
+            // A 1/24 pulse is rejected by the constructors, the fastest pulse is the closest mode
+            LedAnimation::Pulse(LedRate::OneOver24 | LedRate::OneOver16) => Self::Pulsing1over16,
+            LedAnimation::Pulse(LedRate::OneOver8) => Self::Pulsing1over8,
+            LedAnimation::Pulse(LedRate::OneOver4) => Self::Pulsing1over4,
+            LedAnimation::Pulse(LedRate::OneOver2) => Self::Pulsing1over2,
+            LedAnimation::Blink(LedRate::OneOver24) => Self::Blinking1over24,
+            LedAnimation::Blink(LedRate::OneOver16) => Self::Blinking1over16,
+            LedAnimation::Blink(LedRate::OneOver8) => Self::Blinking1over8,
+            LedAnimation::Blink(LedRate::OneOver4) => Self::Blinking1over4,
+            LedAnimation::Blink(LedRate::OneOver2) => Self::Blinking1over2,
+        }
+    }
+}
+
+impl From<LedMode> for LedBehavior {
+    fn from(mode: LedMode) -> Self {
+        match mode {
+            LedMode::On10Percent => Self::solid(Brightness::Percent10),
+            LedMode::On25Percent => Self::solid(Brightness::Percent25),
+            LedMode::On50Percent => Self::solid(Brightness::Percent50),
+            LedMode::On65Percent => Self::solid(Brightness::Percent65),
+            LedMode::On75Percent => Self::solid(Brightness::Percent75),
+            LedMode::On90Percent => Self::solid(Brightness::Percent90),
+            LedMode::On100Percent => Self::solid(Brightness::Percent100),
+            LedMode::Pulsing1over16 => Self::pulsing(LedRate::OneOver16),
+            LedMode::Pulsing1over8 => Self::pulsing(LedRate::OneOver8),
+            LedMode::Pulsing1over4 => Self::pulsing(LedRate::OneOver4),
+            LedMode::Pulsing1over2 => Self::pulsing(LedRate::OneOver2),
+            LedMode::Blinking1over24 => Self::blink(LedRate::OneOver24),
+            LedMode::Blinking1over16 => Self::blink(LedRate::OneOver16),
+            LedMode::Blinking1over8 => Self::blink(LedRate::OneOver8),
+            LedMode::Blinking1over4 => Self::blink(LedRate::OneOver4),
+            LedMode::Blinking1over2 => Self::blink(LedRate::OneOver2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Brightness, LedAnimation, LedBehavior, LedRate};
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::errors::led::LedError;
+    use crate::io::output::ChannelOutput;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use midi_msg::Channel;
+    use std::error::Error;
+
+    #[test]
+    fn every_mode_round_trips_through_its_behavior() -> Result<(), Box<dyn Error>> {
+        for status in 0x90..=0x9F {
+            let mode = LedMode::try_from(status)?;
+            let behavior = LedBehavior::from(mode);
+            assert!(LedBehavior::is_supported(
+                behavior.brightness(),
+                behavior.animation()
+            ));
+            assert_eq!(LedMode::from(behavior), mode);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_combinations_are_rejected() {
+        assert!(matches!(
+            LedBehavior::new(
+                Brightness::Percent50,
+                LedAnimation::Blink(LedRate::OneOver4)
+            ),
+            Err(LedError::UnsupportedBehavior(_))
+        ));
+        assert!(LedBehavior::pulse(LedRate::OneOver24).is_err());
+        assert_eq!(
+            LedBehavior::new(
+                Brightness::Percent100,
+                LedAnimation::Pulse(LedRate::OneOver2)
+            )
+            .ok(),
+            LedBehavior::pulse(LedRate::OneOver2).ok()
+        );
+    }
+
+    #[test]
+    fn brightness_steps_cover_every_level_once() {
+        let mut level = Some(Brightness::Percent10);
+        let mut levels = Vec::new();
+        while let Some(current) = level {
+            levels.push(current);
+            level = current.brighter();
+        }
+        assert_eq!(levels, Brightness::ALL);
+        assert_eq!(Brightness::Percent10.dimmer(), None);
+        assert_eq!(Brightness::from_percent(60), Brightness::Percent65);
+        assert_eq!(Brightness::from_percent(200), Brightness::Percent100);
+    }
+
+    #[test]
+    fn behaviors_are_sent_as_their_mode() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let dim = LedBehavior::solid(Brightness::Percent25);
+        midi_out.set_pad_led(dim, 0, LedColor::Red)?;
+        midi_out.set_pad_led(LedBehavior::blink(LedRate::OneOver8), 1, LedColor::Red)?;
+        assert_eq!(
+            device.received(),
+            vec![vec![0x91, 0x00, 0x05], vec![0x9D, 0x01, 0x05]]
+        );
+        assert_eq!(dim.channel(), Channel::Ch2);
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use num_enum::TryFromPrimitive;
+
+/// States supported by the single colour LEDs of the non-pad buttons
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u8)]
+pub enum ButtonLedState {
+    #[default]
+    Off = 0,
+    On = 1,
+    Blinking = 2,
+}
+
+impl From<ButtonLedState> for u8 {
+    fn from(state: ButtonLedState) -> Self {
+        state as Self
+    }
+}
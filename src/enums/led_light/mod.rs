@@ -1,2 +1,6 @@
+pub mod behavior;
+pub mod button_state;
 pub mod color;
 pub mod mode;
+pub mod palette;
+pub mod rgb;
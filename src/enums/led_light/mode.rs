@@ -1,6 +1,6 @@
 use num_enum::TryFromPrimitive;
 
-#[derive(Debug, Copy, Clone, Default, TryFromPrimitive)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum LedMode {
     On10Percent = 0x90,
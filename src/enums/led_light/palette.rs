@@ -0,0 +1,187 @@
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::rgb::RgbColor;
+use crate::errors::led::LedError;
+use std::str::FromStr;
+
+/// Approximate colour shown by every `LedColor`, indexed by velocity.
+///
+/// The hardware renders them through its own LED response, so they are a close guide rather than a calibrated match
+#[rustfmt::skip]
+const PALETTE: [u32; 128] = [
+    0x00_0000, 0x1E_1E1E, 0x7F_7F7F, 0xFF_FFFF, 0xFF_4C4C, 0xFF_0000, 0x59_0000, 0x19_0000,
+    0xFF_BD6C, 0xFF_5400, 0x59_1D00, 0x27_1B00, 0xFF_FF4C, 0xFF_FF00, 0x59_5900, 0x19_1900,
+    0x88_FF4C, 0x54_FF00, 0x1D_5900, 0x14_2B00, 0x4C_FF4C, 0x00_FF00, 0x00_5900, 0x00_1900,
+    0x4C_FF5E, 0x00_FF19, 0x00_590D, 0x00_1902, 0x4C_FF88, 0x00_FF55, 0x00_591D, 0x00_1F12,
+    0x4C_FFB7, 0x00_FF99, 0x00_5935, 0x00_1912, 0x4C_C3FF, 0x00_A9FF, 0x00_4152, 0x00_1019,
+    0x4C_88FF, 0x00_55FF, 0x00_1D59, 0x00_0819, 0x4C_4CFF, 0x00_00FF, 0x00_0059, 0x00_0019,
+    0x87_4CFF, 0x54_00FF, 0x19_0064, 0x0F_0030, 0xFF_4CFF, 0xFF_00FF, 0x59_0059, 0x19_0019,
+    0xFF_4C87, 0xFF_0054, 0x59_001D, 0x22_0013, 0xFF_1500, 0x99_3500, 0x79_5100, 0x43_6400,
+    0x03_3900, 0x00_5735, 0x00_547F, 0x00_00FF, 0x00_454F, 0x25_00CC, 0x7F_7F7F, 0x20_2020,
+    0xFF_0000, 0xBD_FF2D, 0xAF_ED06, 0x64_FF09, 0x10_8B00, 0x00_FF87, 0x00_A9FF, 0x00_2AFF,
+    0x3F_00FF, 0x7A_00FF, 0xB2_1A7D, 0x40_2100, 0xFF_4A00, 0x88_E106, 0x72_FF15, 0x00_FF00,
+    0x3B_FF26, 0x59_FF71, 0x38_FFCC, 0x5B_8AFF, 0x31_51C6, 0x87_7FE9, 0xD3_1DFF, 0xFF_005D,
+    0xFF_7F00, 0xB9_B000, 0x90_FF00, 0x83_5D07, 0x39_2B00, 0x14_4C10, 0x0D_5038, 0x15_152A,
+    0x16_205A, 0x69_3C1C, 0xA8_000A, 0xDE_513D, 0xD8_6A1C, 0xFF_E126, 0x9E_E12F, 0x67_B50F,
+    0x1E_1E30, 0xDC_FF6B, 0x80_FFBD, 0x9A_99FF, 0x8E_66FF, 0x40_4040, 0x75_7575, 0xE0_FFFF,
+    0xA0_0000, 0x35_0000, 0x1A_D000, 0x07_4200, 0xB9_B000, 0x3F_3100, 0xB3_5F00, 0x4B_1502,
+];
+
+impl LedColor {
+    /// Every palette entry, in velocity order
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..=127).filter_map(|velocity| Self::try_from(velocity).ok())
+    }
+
+    /// Approximate colour shown by the pad
+    #[must_use]
+    pub const fn rgb(self) -> RgbColor {
+        let [_, red, green, blue] = PALETTE[self as usize].to_be_bytes();
+        RgbColor::new(red, green, blue)
+    }
+
+    /// Palette entry that looks the closest to the given colour
+    /// ```Rust
+    ///assert_eq!(LedColor::nearest(RgbColor::new(250, 5, 10)), LedColor::Red);
+    /// ```
+    #[must_use]
+    pub fn nearest(color: RgbColor) -> Self {
+        Self::all()
+            .min_by_key(|candidate| color.distance(candidate.rgb()))
+            .unwrap_or_default()
+    }
+
+    /// Same as `nearest`, hue in degrees, saturation and value from 0 to 1
+    #[must_use]
+    pub fn nearest_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        Self::nearest(RgbColor::from_hsv(hue, saturation, value))
+    }
+
+    /// `steps` palette entries going from `from` to `to`, both included, interpolated in RGB
+    /// ```Rust
+    ///let fade = LedColor::gradient(LedColor::Red, LedColor::Blue2, 8);
+    /// ```
+    #[must_use]
+    pub fn gradient<F, T>(from: F, to: T, steps: usize) -> Vec<Self>
+    where
+        F: Into<RgbColor>,
+        T: Into<RgbColor>,
+    {
+        let (from, to) = (from.into(), to.into());
+        gradient_positions(steps)
+            .map(|t| Self::nearest(from.lerp(to, t)))
+            .collect()
+    }
+
+    /// `steps` palette entries around the colour wheel, at full saturation and the given value
+    #[must_use]
+    pub fn rainbow(steps: usize, value: f32) -> Vec<Self> {
+        let step_hue = 360.0 / steps.max(1) as f32;
+        (0..steps)
+            .map(|i| Self::nearest_hsv(i as f32 * step_hue, 1.0, value))
+            .collect()
+    }
+}
+
+impl From<LedColor> for RgbColor {
+    fn from(color: LedColor) -> Self {
+        color.rgb()
+    }
+}
+
+impl From<RgbColor> for LedColor {
+    fn from(color: RgbColor) -> Self {
+        Self::nearest(color)
+    }
+}
+
+impl FromStr for LedColor {
+    type Err = LedError;
+
+    /// Matches the variant names, ignoring case, spaces, `-` and `_`: `"dark teal 2"` is `LedColor::DarkTeal2`
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let wanted = normalize_name(name);
+        Self::all()
+            .find(|color| normalize_name(&format!("{color:?}")) == wanted)
+            .ok_or_else(|| LedError::UnknownColorName(name.to_owned()))
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn gradient_positions(steps: usize) -> impl Iterator<Item = f32> {
+    let last = steps.saturating_sub(1).max(1) as f32;
+    (0..steps).map(move |i| i as f32 / last)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::errors::led::LedError;
+    use std::error::Error;
+
+    #[test]
+    fn nearest_finds_the_palette_colours() {
+        for color in LedColor::all() {
+            assert_eq!(LedColor::nearest(color.rgb()).rgb(), color.rgb());
+        }
+        assert_eq!(LedColor::nearest(RgbColor::BLACK), LedColor::Off);
+        assert_eq!(LedColor::nearest(RgbColor::WHITE), LedColor::White);
+        assert_eq!(LedColor::nearest(RgbColor::new(250, 5, 10)), LedColor::Red);
+        assert_eq!(LedColor::from(RgbColor::new(0, 250, 5)), LedColor::Green);
+    }
+
+    #[test]
+    fn names_ignore_case_spaces_and_separators() -> Result<(), Box<dyn Error>> {
+        assert_eq!("dark teal 2".parse::<LedColor>()?, LedColor::DarkTeal2);
+        assert_eq!("DARK_TEAL-2".parse::<LedColor>()?, LedColor::DarkTeal2);
+        assert_eq!(" DarkTeal2 ".parse::<LedColor>()?, LedColor::DarkTeal2);
+        for color in LedColor::all() {
+            assert_eq!(format!("{color:?}").parse::<LedColor>()?, color);
+        }
+        assert!(matches!(
+            "dark teal".parse::<LedColor>(),
+            Err(LedError::UnknownColorName(name)) if name == "dark teal"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn gradients_include_both_ends() {
+        assert!(LedColor::gradient(LedColor::Red, LedColor::BrightBlue, 0).is_empty());
+        assert_eq!(
+            LedColor::gradient(LedColor::Red, LedColor::BrightBlue, 1),
+            vec![LedColor::Red]
+        );
+        assert_eq!(
+            LedColor::gradient(LedColor::Red, LedColor::BrightBlue, 2),
+            vec![LedColor::Red, LedColor::BrightBlue]
+        );
+        let fade = LedColor::gradient(RgbColor::WHITE, RgbColor::BLACK, 8);
+        assert_eq!(fade.len(), 8);
+        assert_eq!(fade.first(), Some(&LedColor::White));
+        assert_eq!(fade.last(), Some(&LedColor::Off));
+    }
+
+    #[test]
+    fn rainbow_goes_around_the_colour_wheel() {
+        assert!(LedColor::rainbow(0, 1.0).is_empty());
+        let rainbow = LedColor::rainbow(6, 1.0);
+        assert_eq!(rainbow.len(), 6);
+        assert_eq!(rainbow[0], LedColor::Red);
+        assert_eq!(rainbow[1], LedColor::BrightYellow);
+        assert_eq!(rainbow[2], LedColor::Green);
+        assert_eq!(rainbow[4], LedColor::BrightBlue);
+        assert_eq!(rainbow[5], LedColor::Magenta);
+        assert!(
+            LedColor::rainbow(6, 0.0)
+                .into_iter()
+                .all(|color| color == LedColor::Off)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+/// True colour for the pads, sent with the mk2 RGB system exclusive message instead of the velocity palette of `LedColor`
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RgbColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl RgbColor {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+
+    #[must_use]
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Parses `#RRGGBB` or `RRGGBB`
+    #[must_use]
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// System exclusive data bytes are 7 bit wide, so every component is split in its high bit and its low 7 bits
+    #[must_use]
+    pub const fn to_sysex_bytes(self) -> [u8; 6] {
+        [
+            self.red >> 7,
+            self.red & 0x7F,
+            self.green >> 7,
+            self.green & 0x7F,
+            self.blue >> 7,
+            self.blue & 0x7F,
+        ]
+    }
+
+    /// Inverse of `to_sysex_bytes`
+    #[must_use]
+    pub const fn from_sysex_bytes(bytes: [u8; 6]) -> Self {
+        Self::new(
+            (bytes[0] << 7) | (bytes[1] & 0x7F),
+            (bytes[2] << 7) | (bytes[3] & 0x7F),
+            (bytes[4] << 7) | (bytes[5] & 0x7F),
+        )
+    }
+
+    /// Hue in degrees, saturation and value from 0 to 1
+    #[must_use]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation.clamp(0.0, 1.0);
+        let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (red, green, blue) = match hue as i32 {
+            0 => (chroma, second, 0.0),
+            1 => (second, chroma, 0.0),
+            2 => (0.0, chroma, second),
+            3 => (0.0, second, chroma),
+            4 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second),
+        };
+        let component = |c: f32| to_byte((c + value - chroma) * 255.0);
+        Self::new(component(red), component(green), component(blue))
+    }
+
+    /// Hue in degrees, saturation and value from 0 to 1
+    #[must_use]
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let [red, green, blue] = [self.red, self.green, self.blue].map(|c| f32::from(c) / 255.0);
+        let max = red.max(green).max(blue);
+        let chroma = max - red.min(green).min(blue);
+        let hue = if chroma <= 0.0 {
+            0.0
+        } else if (max - red).abs() <= f32::EPSILON {
+            60.0 * ((green - blue) / chroma).rem_euclid(6.0)
+        } else if (max - green).abs() <= f32::EPSILON {
+            60.0 * ((blue - red) / chroma + 2.0)
+        } else {
+            60.0 * ((red - green) / chroma + 4.0)
+        };
+        let saturation = if max <= 0.0 { 0.0 } else { chroma / max };
+        (hue, saturation, max)
+    }
+
+    /// Linear interpolation towards `other`, `t` from 0 (self) to 1 (other)
+    #[must_use]
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| to_byte((f32::from(b) - f32::from(a)).mul_add(t, f32::from(a)));
+        Self::new(
+            mix(self.red, other.red),
+            mix(self.green, other.green),
+            mix(self.blue, other.blue),
+        )
+    }
+
+    /// Squared "redmean" distance, an euclidean distance with the channels weighted after the mean red level
+    /// to follow the eye sensitivity better than plain RGB
+    #[must_use]
+    pub fn distance(self, other: Self) -> u32 {
+        let red_mean = u32::midpoint(u32::from(self.red), u32::from(other.red));
+        let squared = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+        ((512 + red_mean) * squared(self.red, other.red)) / 256
+            + 4 * squared(self.green, other.green)
+            + ((767 - red_mean) * squared(self.blue, other.blue)) / 256
+    }
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn to_byte(component: f32) -> u8 {
+    component.round().clamp(0.0, 255.0) as u8
+}
+
+impl From<(u8, u8, u8)> for RgbColor {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        Self::new(red, green, blue)
+    }
+}
+
+impl From<RgbColor> for (u8, u8, u8) {
+    fn from(color: RgbColor) -> Self {
+        (color.red, color.green, color.blue)
+    }
+}
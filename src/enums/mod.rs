@@ -2,3 +2,5 @@ pub mod button;
 pub mod input_group;
 pub mod led_light;
 pub mod message_filter;
+pub mod overflow_policy;
+pub mod port_selector;
@@ -0,0 +1,9 @@
+/// What to do with a new input event when the buffer between the MIDI thread and the consumer is full
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Discard the oldest buffered event to make room, the consumer always sees the most recent input
+    #[default]
+    DropOldest,
+    /// Discard the incoming event, the consumer sees the input in the order it started piling up
+    DropNewest,
+}
@@ -0,0 +1,104 @@
+use std::ptr;
+
+/// Port name fragment reported by the APC Key 25 on every platform
+pub const APC_KEY_25_PORT_NAME: &str = "APC Key 25";
+
+#[derive(Debug, Clone, Default)]
+/// An enum that is used to specify which MIDI port should be opened.
+pub enum PortSelector {
+    /// Position of the port in the list returned by the backend
+    Index(usize),
+    /// Case-insensitive substring of the port name
+    Name(String),
+    /// Port names accepted by the function, e.g. a regular expression match
+    /// ```Rust
+    ///static PORT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^apc key 25( mk2)?$").unwrap());
+    ///let selector = PortSelector::Matching(|name| PORT.is_match(name));
+    /// ```
+    Matching(fn(&str) -> bool),
+    /// First port whose name contains `APC_KEY_25_PORT_NAME`
+    #[default]
+    Auto,
+}
+
+impl PortSelector {
+    /// Checks whether the given port name is a match, always false for `Index`
+    #[must_use]
+    pub fn matches_name(&self, port_name: &str) -> bool {
+        let pattern = match self {
+            Self::Index(_) => return false,
+            Self::Matching(predicate) => return predicate(port_name),
+            Self::Name(pattern) => pattern.as_str(),
+            Self::Auto => APC_KEY_25_PORT_NAME,
+        };
+        port_name
+            .to_lowercase()
+            .contains(pattern.to_lowercase().as_str())
+    }
+}
+
+/// `Matching` selectors are equal when they hold the same function, as far as `ptr::fn_addr_eq` can tell
+impl PartialEq for PortSelector {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Index(index), Self::Index(other)) => index == other,
+            (Self::Name(pattern), Self::Name(other)) => pattern == other,
+            (Self::Matching(predicate), Self::Matching(other)) => {
+                ptr::fn_addr_eq(*predicate, *other)
+            }
+            (Self::Auto, Self::Auto) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for PortSelector {}
+
+impl From<usize> for PortSelector {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl From<&str> for PortSelector {
+    fn from(pattern: &str) -> Self {
+        Self::Name(pattern.to_string())
+    }
+}
+
+impl From<String> for PortSelector {
+    fn from(pattern: String) -> Self {
+        Self::Name(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PortSelector;
+
+    #[test]
+    fn names_match_case_insensitive_substrings() {
+        let selector = PortSelector::from("key 25");
+        assert!(selector.matches_name("APC Key 25 mk2"));
+        assert!(selector.matches_name("apc KEY 25:APC Key 25 MIDI 1 20:0"));
+        assert!(!selector.matches_name("APC40 mkII"));
+        assert!(PortSelector::from(String::new()).matches_name("Midi Through"));
+    }
+
+    #[test]
+    fn predicates_get_the_whole_port_name() {
+        let selector =
+            PortSelector::Matching(|name| name.starts_with("APC") && name.ends_with(" 1"));
+        assert!(selector.matches_name("APC Key 25 mk2 MIDI 1"));
+        assert!(!selector.matches_name("apc Key 25 mk2 MIDI 1"));
+        assert!(!selector.matches_name("APC Key 25 mk2 MIDI 2"));
+    }
+
+    #[test]
+    fn auto_matches_the_apc_key_25_only() {
+        assert!(PortSelector::Auto.matches_name("APC Key 25 mk2"));
+        assert!(PortSelector::Auto.matches_name("MIDIIN2 (APC KEY 25)"));
+        assert!(!PortSelector::Auto.matches_name("Midi Through Port-0"));
+        assert!(!PortSelector::Index(0).matches_name("APC Key 25"));
+    }
+}
@@ -1,10 +1,17 @@
+use crate::enums::port_selector::PortSelector;
 use midi_msg::ParseError;
 use midir::{ConnectError, InitError, MidiInput, MidiOutput, SendError};
+use std::time::Duration;
 use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum ChannelCreationError {
     #[error("Port {0} not in valid range, check the available range of ports")]
     PortOutOfRange(usize),
+    #[error("No port matching {selector:?}, available ports are {available:?}")]
+    NoMatchingPort {
+        selector: PortSelector,
+        available: Vec<String>,
+    },
     #[error("error {source} when initializing channel")]
     InitializingChannel {
         #[source]
@@ -20,6 +27,8 @@ pub enum ChannelCreationError {
         #[source]
         source: ConnectError<MidiOutput>,
     },
+    #[error("Input already consumed by a previous listener")]
+    AlreadyListening,
     #[error("error {source} when trying to initialize led")]
     LedInitialization {
         #[source]
@@ -35,6 +44,8 @@ pub enum TransmissionError {
         #[source]
         source: SendError,
     },
+    #[error("Device disconnected when transmitting {:?}", data)]
+    Disconnected { data: Vec<u8> },
     #[error("error {source} when decoding {:?}", data)]
     Receive {
         data: Vec<u8>,
@@ -42,3 +53,19 @@ pub enum TransmissionError {
         source: ParseError,
     },
 }
+
+#[derive(Error, Debug)]
+pub enum DeviceInquiryError {
+    #[error("error {source} when listening for the inquiry reply")]
+    Listening {
+        #[source]
+        source: ChannelCreationError,
+    },
+    #[error("error {source} when sending the inquiry request")]
+    Request {
+        #[source]
+        source: TransmissionError,
+    },
+    #[error("No inquiry reply received in {0:?}, check that SysEx messages are not filtered")]
+    Timeout(Duration),
+}
@@ -0,0 +1,15 @@
+use crate::enums::led_light::behavior::LedBehavior;
+use thiserror::Error;
+#[derive(Error, Debug)]
+pub enum LedError {
+    #[error(
+        "No LedColor named {0:?}, names are the variant names, case and separators are ignored"
+    )]
+    UnknownColorName(String),
+    #[error(
+        "{0:?} is not supported, pulsing and blinking are only available at full brightness and pulsing is not available at 1/24"
+    )]
+    UnsupportedBehavior(LedBehavior),
+    #[error("No LED layer named {0:?}")]
+    UnknownLayer(String),
+}
@@ -9,4 +9,12 @@ pub enum HardwareMappingError {
     InvalidSoftKeyIndex(u8),
     #[error("Number {0} not in valid range, check the available range of numbers")]
     InvalidKeyboardKeyIndex(u8),
+    #[error("Number {0} not in valid range, check the available range of numbers")]
+    InvalidKnobIndex(u8),
+    #[error("Note {0} has no button LED, check the available range of notes")]
+    InvalidButtonLedIndex(u8),
+    #[error("Row {0}, column {1} is outside the pad grid, check the available rows and columns")]
+    InvalidPadPosition(u8, u8),
+    #[error("Coordinates x {x}, y {y} are outside the pad grid, check the grid size")]
+    InvalidPadCoordinates { x: u8, y: u8 },
 }
@@ -1,2 +1,3 @@
 pub mod io;
+pub mod led;
 pub mod mapping;
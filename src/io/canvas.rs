@@ -0,0 +1,318 @@
+use crate::enums::button::geometry::Origin;
+use crate::enums::button::led_button::LedButton;
+use crate::enums::button::pads::{GRID_COLUMNS, GRID_ROWS, PadKey};
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::enums::led_light::rgb::RgbColor;
+use crate::errors::io::TransmissionError;
+use crate::io::frame::{FrameBuffer, PadLed, PadLight};
+use crate::io::output::ChannelOutput;
+use crate::io::transport::MidiSink;
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::{PixelColor, Rgb888, RgbColor as _};
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+impl PixelColor for LedColor {
+    type Raw = ();
+}
+
+impl From<Rgb888> for RgbColor {
+    fn from(color: Rgb888) -> Self {
+        Self::new(color.r(), color.g(), color.b())
+    }
+}
+
+impl From<RgbColor> for Rgb888 {
+    fn from(color: RgbColor) -> Self {
+        Self::new(color.red, color.green, color.blue)
+    }
+}
+
+/// Pixel colours a `PadCanvas` can show
+pub trait CanvasColor: PixelColor {
+    /// Light of a pad showing the colour, `led_mode` only applies to palette colours
+    fn to_pad_light(self, led_mode: LedMode) -> PadLight;
+
+    /// Colour shown by the pad, `None` when the light is not of this kind of colour
+    fn from_pad_light(light: PadLight) -> Option<Self>;
+}
+
+impl CanvasColor for LedColor {
+    fn to_pad_light(self, led_mode: LedMode) -> PadLight {
+        PadLight::Palette(PadLed {
+            mode: led_mode,
+            color: self,
+        })
+    }
+
+    fn from_pad_light(light: PadLight) -> Option<Self> {
+        match light {
+            PadLight::Palette(pad) => Some(pad.color),
+            PadLight::Rgb(_) => None,
+        }
+    }
+}
+
+impl CanvasColor for Rgb888 {
+    fn to_pad_light(self, _: LedMode) -> PadLight {
+        PadLight::Rgb(self.into())
+    }
+
+    fn from_pad_light(light: PadLight) -> Option<Self> {
+        match light {
+            PadLight::Rgb(color) => Some(color.into()),
+            PadLight::Palette(_) => None,
+        }
+    }
+}
+
+/// `embedded-graphics` draw target over the pad grid, one pixel per pad with (0, 0) on the top left pad.
+///
+/// Drawing only touches the `FrameBuffer` behind the canvas, `flush` sends the lights that changed since the previous flush.
+/// With `LedColor` pixels the pads use the palette, with `Rgb888` pixels they use the mk2 RGB message.
+/// The frame holds the button LEDs as well, they stay off unless set with `set_button`
+/// ```Rust
+///let mut canvas = PadCanvas::<LedColor>::default();
+///Line::new(Point::new(0, 0), Point::new(7, 4))
+///    .into_styled(PrimitiveStyle::with_stroke(LedColor::Red, 1))
+///    .draw(&mut canvas)?;
+///canvas.flush(&mut midi_out)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PadCanvas<C> {
+    frame_buffer: FrameBuffer,
+    led_mode: LedMode,
+    color: PhantomData<C>,
+}
+
+impl Default for PadCanvas<LedColor> {
+    fn default() -> Self {
+        Self::filled(LedColor::Off)
+    }
+}
+
+impl Default for PadCanvas<Rgb888> {
+    fn default() -> Self {
+        Self::filled(Rgb888::BLACK)
+    }
+}
+
+impl<C: CanvasColor> PadCanvas<C> {
+    fn filled(color: C) -> Self {
+        let mut canvas = Self {
+            frame_buffer: FrameBuffer::default(),
+            led_mode: LedMode::default(),
+            color: PhantomData,
+        };
+        for pad in PadKey::all() {
+            canvas.set_pixel(pad, color);
+        }
+        canvas
+    }
+
+    /// Pad shown by the pixel, `None` outside the grid
+    #[must_use]
+    pub fn pad_at(point: Point) -> Option<PadKey> {
+        let (x, y) = (u8::try_from(point.x).ok()?, u8::try_from(point.y).ok()?);
+        PadKey::from_xy(x, y, Origin::TopLeft).ok()
+    }
+
+    /// Pixel showing the pad
+    #[must_use]
+    pub fn point_of(pad: PadKey) -> Point {
+        let (x, y) = pad.to_xy(Origin::TopLeft);
+        Point::new(i32::from(x), i32::from(y))
+    }
+
+    /// Colour of the pad in the buffer, which may not have been flushed yet
+    #[must_use]
+    pub fn pixel(&self, pad: PadKey) -> Option<C> {
+        self.frame_buffer
+            .pad_light(pad)
+            .ok()
+            .and_then(C::from_pad_light)
+    }
+
+    pub fn set_button<T>(&mut self, button: T, state: ButtonLedState)
+    where
+        T: Into<LedButton>,
+    {
+        self.frame_buffer.set_button(button, state);
+    }
+
+    /// Sends the lights that changed since the last flush and returns how many messages were sent,
+    /// the RGB pads go out in a single message
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn flush<O: MidiSink>(
+        &mut self,
+        output: &mut ChannelOutput<O>,
+    ) -> Result<usize, TransmissionError> {
+        self.frame_buffer.flush(output)
+    }
+
+    /// Forgets what the device is showing, the next flush will send every light
+    pub const fn invalidate(&mut self) {
+        self.frame_buffer.invalidate();
+    }
+
+    fn set_pixel(&mut self, pad: PadKey, color: C) {
+        self.frame_buffer
+            .set_pad_light(pad, color.to_pad_light(self.led_mode));
+    }
+}
+
+impl PadCanvas<LedColor> {
+    /// Mode used to show the palette colours, `LedMode::On100Percent` by default
+    pub fn set_led_mode<M>(&mut self, led_mode: M)
+    where
+        M: Into<LedMode>,
+    {
+        self.led_mode = led_mode.into();
+        for pad in PadKey::all() {
+            if let Some(color) = self.pixel(pad) {
+                self.set_pixel(pad, color);
+            }
+        }
+    }
+}
+
+impl<C: CanvasColor> OriginDimensions for PadCanvas<C> {
+    fn size(&self) -> Size {
+        Size::new(u32::from(GRID_COLUMNS), u32::from(GRID_ROWS))
+    }
+}
+
+impl<C: CanvasColor> DrawTarget for PadCanvas<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    /// Pixels outside the grid are ignored
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(pad) = Self::pad_at(point) {
+                self.set_pixel(pad, color);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PadCanvas;
+    use crate::enums::button::led_button::LedButton;
+    use crate::enums::button::pads::{PAD_COUNT, PadKey};
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::io::output::{ChannelOutput, rgb_sysex};
+    use crate::io::transport::loopback::LoopbackDevice;
+    use embedded_graphics_core::Pixel;
+    use embedded_graphics_core::draw_target::DrawTarget;
+    use embedded_graphics_core::geometry::{Point, Size};
+    use embedded_graphics_core::pixelcolor::Rgb888;
+    use embedded_graphics_core::primitives::Rectangle;
+    use std::error::Error;
+
+    const EVERY_LIGHT: usize = PAD_COUNT as usize + LedButton::ALL.len();
+
+    #[test]
+    fn flush_sends_the_drawn_pads_then_only_the_changes() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut canvas = PadCanvas::<LedColor>::default();
+        canvas.flush(&mut midi_out)?;
+        assert_eq!(device.take_received().len(), EVERY_LIGHT);
+
+        // Top right corner, half of the rectangle is outside the grid
+        canvas.fill_solid(
+            &Rectangle::new(Point::new(6, -1), Size::new(4, 3)),
+            LedColor::Red,
+        )?;
+        assert_eq!(canvas.pixel(PadKey::Grid(2, 8)), Some(LedColor::Red));
+        assert_eq!(canvas.flush(&mut midi_out)?, 4);
+        assert_eq!(
+            device.take_received(),
+            vec![
+                vec![0x96, 0x1E, 0x05],
+                vec![0x96, 0x1F, 0x05],
+                vec![0x96, 0x26, 0x05],
+                vec![0x96, 0x27, 0x05],
+            ]
+        );
+
+        canvas.set_led_mode(LedMode::On50Percent);
+        assert_eq!(canvas.flush(&mut midi_out)?, usize::from(PAD_COUNT));
+        let _ = device.take_received();
+        canvas.draw_iter([
+            Pixel(Point::new(7, 0), LedColor::Red),
+            Pixel(Point::new(0, 4), LedColor::Green),
+        ])?;
+        canvas.set_button(SoftKey::Mute, ButtonLedState::On);
+        assert_eq!(canvas.flush(&mut midi_out)?, 2);
+        assert_eq!(
+            device.received(),
+            vec![
+                vec![0x92, 0x00, 0x15],
+                vec![0x90, u8::from(SoftKey::Mute), 0x01]
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rgb_pixels_go_out_in_one_message() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut canvas = PadCanvas::<Rgb888>::default();
+        canvas.flush(&mut midi_out)?;
+        assert!(
+            device
+                .take_received()
+                .contains(&rgb_sysex(&[(0..=39, RgbColor::BLACK)]))
+        );
+        canvas.fill_solid(
+            &Rectangle::new(Point::new(0, 4), Size::new(2, 1)),
+            Rgb888::new(255, 128, 0),
+        )?;
+        canvas.draw_iter([Pixel(Point::new(7, 4), Rgb888::new(0, 0, 255))])?;
+        assert_eq!(
+            canvas.pixel(PadKey::Grid(5, 2)),
+            Some(Rgb888::new(255, 128, 0))
+        );
+        assert_eq!(canvas.flush(&mut midi_out)?, 1);
+        assert_eq!(
+            device.received(),
+            vec![rgb_sysex(&[
+                (0..=1, RgbColor::new(255, 128, 0)),
+                (7..=7, RgbColor::new(0, 0, 255))
+            ])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_flush_sends_every_light_next_time() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut canvas = PadCanvas::<LedColor>::default();
+        canvas.flush(&mut midi_out)?;
+        canvas.clear(LedColor::Blue1)?;
+        device.unplug();
+        assert!(canvas.flush(&mut midi_out).is_err());
+        device.plug_in();
+        assert_eq!(canvas.flush(&mut midi_out)?, EVERY_LIGHT);
+        Ok(())
+    }
+}
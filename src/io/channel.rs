@@ -1,3 +1,4 @@
+use crate::enums::port_selector::PortSelector;
 use crate::errors::io::ChannelCreationError;
 use midir::MidiIO;
 use std::collections::HashMap;
@@ -21,3 +22,97 @@ pub trait Channel {
     }
     fn get_midi() -> Result<impl MidiIO, ChannelCreationError>;
 }
+
+/// Picks the port described by the selector among the ones currently exposed by the backend
+pub fn select_port<M: MidiIO>(
+    midi_io: &M,
+    selector: &PortSelector,
+) -> Result<M::Port, ChannelCreationError> {
+    let ports = midi_io.ports();
+    if let PortSelector::Index(index) = selector {
+        return ports
+            .get(*index)
+            .cloned()
+            .ok_or(ChannelCreationError::PortOutOfRange(*index));
+    }
+    let named_ports: Vec<(M::Port, String)> = ports
+        .into_iter()
+        .filter_map(|p| midi_io.port_name(&p).map(|p_name| (p, p_name)).ok())
+        .collect();
+    named_ports
+        .iter()
+        .find(|(_, p_name)| selector.matches_name(p_name))
+        .map(|(p, _)| p.clone())
+        .ok_or_else(|| ChannelCreationError::NoMatchingPort {
+            selector: selector.clone(),
+            available: named_ports.into_iter().map(|(_, p_name)| p_name).collect(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_port;
+    use crate::enums::port_selector::PortSelector;
+    use crate::errors::io::ChannelCreationError;
+    use midir::{MidiIO, PortInfoError};
+
+    /// Backend exposing fixed port names, the port is its position in the list
+    struct Ports(&'static [&'static str]);
+
+    impl MidiIO for Ports {
+        type Port = usize;
+
+        fn ports(&self) -> Vec<usize> {
+            (0..self.0.len()).collect()
+        }
+
+        fn port_count(&self) -> usize {
+            self.0.len()
+        }
+
+        fn port_name(&self, port: &usize) -> Result<String, PortInfoError> {
+            self.0
+                .get(*port)
+                .map(ToString::to_string)
+                .ok_or(PortInfoError::PortNumberOutOfRange)
+        }
+    }
+
+    const PORTS: Ports = Ports(&["Midi Through Port-0", "LPK25", "APC Key 25 mk2 MIDI 1"]);
+
+    #[test]
+    fn ports_are_picked_by_index_name_or_auto() -> Result<(), ChannelCreationError> {
+        assert_eq!(select_port(&PORTS, &PortSelector::Index(1))?, 1);
+        assert_eq!(select_port(&PORTS, &PortSelector::from("lpk"))?, 1);
+        assert_eq!(select_port(&PORTS, &PortSelector::from("port"))?, 0);
+        assert_eq!(select_port(&PORTS, &PortSelector::Auto)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn index_out_of_range_is_rejected() {
+        assert!(matches!(
+            select_port(&PORTS, &PortSelector::Index(3)),
+            Err(ChannelCreationError::PortOutOfRange(3))
+        ));
+    }
+
+    #[test]
+    fn no_match_lists_the_available_ports() {
+        let Err(err) = select_port(
+            &Ports(&["Midi Through Port-0", "LPK25"]),
+            &PortSelector::Auto,
+        ) else {
+            panic!("nothing should match");
+        };
+        assert!(
+            err.to_string()
+                .contains("\"Midi Through Port-0\", \"LPK25\"")
+        );
+        assert!(matches!(
+            err,
+            ChannelCreationError::NoMatchingPort { selector: PortSelector::Auto, available }
+                if available == ["Midi Through Port-0", "LPK25"]
+        ));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::enums::button::led_button::LedButton;
+use crate::enums::input_group::ChannelKind;
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::enums::led_light::rgb::RgbColor;
+use crate::enums::message_filter::MessageFilter;
+use crate::enums::port_selector::PortSelector;
+use crate::errors::io::{ChannelCreationError, TransmissionError};
+use crate::io::input::InputChannel;
+use crate::io::input_data::MidiInputData;
+use crate::io::notification::Notification;
+use crate::io::output::ChannelOutput;
+use crate::io::sync::lock;
+use crate::io::transport::hardware::MidirInput;
+use crate::io::transport::{MidiSink, MidiSource};
+use bon::bon;
+use midir::MidiOutputConnection;
+use std::any::Any;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+/// Single handle over both directions of the controller.
+///
+/// The output is shared with the listener, so callbacks can drive the LEDs directly.
+/// The handle is `Send`, including while listening, so it can be moved to a worker thread.
+/// Dropping the device closes the input connection first and the output afterward
+pub struct ApcKey25<S = MidirInput, O = MidiOutputConnection> {
+    input: Option<InputChannel<S>>,
+    output: Arc<Mutex<ChannelOutput<O>>>,
+    connection: Option<Box<dyn Any + Send>>,
+}
+
+#[bon]
+impl ApcKey25 {
+    /// Opens input and output on the same device, by default the first port that looks like an APC Key 25.
+    /// Pad LEDs are initialized unless `initialize_note_led` is false
+    #[builder]
+    pub fn new(
+        port_selector: Option<PortSelector>,
+        msg_to_ignore: Option<MessageFilter>,
+        initialize_note_led: Option<bool>,
+    ) -> Result<Self, ChannelCreationError> {
+        let selector = port_selector.unwrap_or_default();
+        let input = InputChannel::builder()
+            .port_selector(selector.clone())
+            .maybe_msg_to_ignore(msg_to_ignore)
+            .build()?;
+        let output = ChannelOutput::builder()
+            .port_selector(selector)
+            .initialize_note_led(initialize_note_led.unwrap_or(true))
+            .build()?;
+        Ok(Self::from_channels(input, output))
+    }
+}
+
+impl<S: MidiSource, O: MidiSink> ApcKey25<S, O> {
+    /// Builds the device on top of any transport, e.g. the two sides of a `LoopbackDevice`
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if the LED initialization cannot be sent
+    pub fn from_transport(
+        source: S,
+        sink: O,
+        initialize_note_led: bool,
+    ) -> Result<Self, TransmissionError> {
+        let mut output = ChannelOutput::from_sink(sink);
+        if initialize_note_led {
+            output.initialize_notes_led()?;
+        }
+        Ok(Self::from_channels(
+            InputChannel::from_source(source),
+            output,
+        ))
+    }
+
+    #[must_use]
+    pub fn from_channels(input: InputChannel<S>, output: ChannelOutput<O>) -> Self {
+        Self {
+            input: Some(input),
+            output: Arc::new(Mutex::new(output)),
+            connection: None,
+        }
+    }
+
+    /// Decodes the incoming messages and calls the given closure with access to the output.
+    /// The connection lives as long as the device, only one listener can be registered
+    /// ```Rust
+    ///let mut device = ramidier::io::device::ApcKey25::builder().build()?;
+    ///device.listen(
+    ///    move |stamp, received_input, midi_out, data| listener_logic(midi_out, stamp, &received_input, data),
+    ///    MyDataStruct{}, // could also be () it there is no need for data
+    ///    PadsAndKnobsChannel, // could also be KeyboardChannel or CombinedChannel depending on your needs
+    /// )?;
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `ChannelCreationError` if the device is already listening or there are low-level issues communicating with it
+    pub fn listen<F, T: Send + 'static, C>(
+        &mut self,
+        mut input_handler_callback: F,
+        data: T,
+        channel_type: C,
+    ) -> Result<(), ChannelCreationError>
+    where
+        C: ChannelKind + Send + 'static,
+        F: FnMut(u64, MidiInputData<C::Group>, &mut ChannelOutput<O>, &mut T) + Send + 'static,
+        O: Send + 'static,
+        S::Connection<T>: Send + 'static,
+    {
+        let input = self
+            .input
+            .take()
+            .ok_or(ChannelCreationError::AlreadyListening)?;
+        let output = Arc::clone(&self.output);
+        let connection = input.listen(
+            None,
+            move |timestamp, input, user_data| {
+                input_handler_callback(timestamp, input, &mut lock(&output), user_data);
+            },
+            data,
+            channel_type,
+        )?;
+        self.connection = Some(Box::new(connection));
+        Ok(())
+    }
+
+    #[must_use]
+    pub const fn is_listening(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Exclusive access to the output, listener callbacks wait until the guard is dropped
+    pub fn output(&self) -> MutexGuard<'_, ChannelOutput<O>> {
+        lock(&self.output)
+    }
+
+    /// Plays the notification on its own thread and restores the lights afterward, see `Notification::spawn`
+    pub fn notify(&self, notification: Notification) -> JoinHandle<Result<(), TransmissionError>>
+    where
+        O: MidiSink + Send + 'static,
+    {
+        notification.spawn(self.shared_output())
+    }
+
+    /// Output handle for other threads, e.g. an `Animator`
+    #[must_use]
+    pub fn shared_output(&self) -> Arc<Mutex<ChannelOutput<O>>> {
+        Arc::clone(&self.output)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if data is not valid or there are low-level issues communicating with the device
+    pub fn send(&self, data: &[u8]) -> Result<(), TransmissionError> {
+        self.output().send(data)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn initialize_notes_led(&self) -> Result<(), TransmissionError> {
+        self.output().initialize_notes_led()
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_all_pads_color<M>(
+        &self,
+        led_mode: M,
+        color: LedColor,
+    ) -> Result<(), TransmissionError>
+    where
+        M: Into<LedMode>,
+    {
+        self.output().set_all_pads_color(led_mode, color)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_pad_led<M, T>(
+        &self,
+        led_mode: M,
+        note: T,
+        color: LedColor,
+    ) -> Result<(), TransmissionError>
+    where
+        M: Into<LedMode>,
+        T: Into<u8>,
+    {
+        self.output().set_pad_led(led_mode, note, color)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_button_led<T>(
+        &self,
+        button: T,
+        state: ButtonLedState,
+    ) -> Result<(), TransmissionError>
+    where
+        T: Into<LedButton>,
+    {
+        self.output().set_button_led(button, state)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_pad_rgb<T>(&self, note: T, color: RgbColor) -> Result<(), TransmissionError>
+    where
+        T: Into<u8>,
+    {
+        self.output().set_pad_rgb(note, color)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_all_pads_rgb(&self, color: RgbColor) -> Result<(), TransmissionError> {
+        self.output().set_all_pads_rgb(color)
+    }
+}
+
+impl<S, O> Drop for ApcKey25<S, O> {
+    fn drop(&mut self) {
+        // Stop the listener first, so nothing writes to the output while it is being closed
+        self.connection.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApcKey25;
+    use crate::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::thread;
+
+    #[test]
+    fn listening_device_moves_to_another_thread() -> Result<(), Box<dyn Error>> {
+        let loopback = LoopbackDevice::new();
+        let mut device = ApcKey25::from_transport(loopback.input(), loopback.output(), false)?;
+        device.listen(
+            |_, input, midi_out, ()| {
+                if let PadsAndKnobsInputGroup::Pads(pad) = input.input_group {
+                    let _ = midi_out.set_pad_led(LedMode::On100Percent, pad, LedColor::Red);
+                }
+            },
+            (),
+            PadsAndKnobsChannel,
+        )?;
+        let worker = thread::spawn(move || {
+            loopback.send(&[0x90, 0x00, 0x7F]);
+            drop(device);
+            loopback.received()
+        });
+        assert_eq!(
+            worker.join().map_err(|_| "worker panicked")?,
+            vec![vec![0x96, 0x00, 0x05]]
+        );
+        Ok(())
+    }
+}
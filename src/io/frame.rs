@@ -0,0 +1,439 @@
+use crate::enums::button::led_button::LedButton;
+use crate::enums::button::pads::{PAD_COUNT, PadKey};
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::enums::led_light::rgb::RgbColor;
+use crate::errors::io::TransmissionError;
+use crate::errors::mapping::HardwareMappingError;
+use crate::io::output::{BUTTON_LED_STATUS, ChannelOutput, parse_rgb_sysex, rgb_sysex};
+use crate::io::transport::MidiSink;
+use std::ops::{Deref, DerefMut, RangeInclusive};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PadLed {
+    pub mode: LedMode,
+    pub color: LedColor,
+}
+
+/// What a pad shows: a palette colour with its mode, or any colour set with the RGB message
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PadLight {
+    Palette(PadLed),
+    /// The RGB message has no mode, the pad is always solid
+    Rgb(RgbColor),
+}
+
+impl Default for PadLight {
+    fn default() -> Self {
+        Self::Palette(PadLed::default())
+    }
+}
+
+impl From<PadLed> for PadLight {
+    fn from(pad: PadLed) -> Self {
+        Self::Palette(pad)
+    }
+}
+
+impl From<RgbColor> for PadLight {
+    fn from(color: RgbColor) -> Self {
+        Self::Rgb(color)
+    }
+}
+
+impl PadLight {
+    /// The light itself for a palette colour, the nearest palette colour at full brightness for an RGB one
+    #[must_use]
+    pub fn to_palette(self) -> PadLed {
+        match self {
+            Self::Palette(pad) => pad,
+            Self::Rgb(color) => PadLed {
+                mode: LedMode::On100Percent,
+                color: LedColor::nearest(color),
+            },
+        }
+    }
+}
+
+/// State of every addressable light of the controller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedFrame {
+    pads: [PadLight; PAD_COUNT as usize],
+    buttons: [ButtonLedState; LedButton::ALL.len()],
+}
+
+impl Default for LedFrame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LedFrame {
+    /// Every light off
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pads: [PadLight::Palette(PadLed {
+                mode: LedMode::On100Percent,
+                color: LedColor::Off,
+            }); PAD_COUNT as usize],
+            buttons: [ButtonLedState::Off; LedButton::ALL.len()],
+        }
+    }
+
+    /// Updates the frame with a pad, RGB or button LED message as sent to the device, returns false for any other message
+    pub fn apply_message(&mut self, data: &[u8]) -> bool {
+        if let Some(ranges) = parse_rgb_sysex(data) {
+            for (pads, color) in ranges {
+                let (start, end) = (*pads.start().min(pads.end()), *pads.start().max(pads.end()));
+                for note in start..=end.min(PAD_COUNT - 1) {
+                    self.pads[usize::from(note)] = PadLight::Rgb(color);
+                }
+            }
+            return true;
+        }
+        let &[status, note, velocity] = data else {
+            return false;
+        };
+        match (
+            LedMode::try_from(status),
+            LedColor::try_from(velocity),
+            LedButton::try_from(note),
+            ButtonLedState::try_from(velocity),
+        ) {
+            (Ok(mode), Ok(color), _, _) if note < PAD_COUNT => {
+                self.pads[usize::from(note)] = PadLight::Palette(PadLed { mode, color });
+                true
+            }
+            (_, _, Ok(button), Ok(state)) if status == BUTTON_LED_STATUS => {
+                self.set_button(button, state);
+                true
+            }
+            _ => false,
+        }
+    }
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn set_pad<M, T>(
+        &mut self,
+        led_mode: M,
+        note: T,
+        color: LedColor,
+    ) -> Result<(), HardwareMappingError>
+    where
+        M: Into<LedMode>,
+        T: Into<u8>,
+    {
+        *self.pad_slot(note.into())? = PadLight::Palette(PadLed {
+            mode: led_mode.into(),
+            color,
+        });
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn set_pad_rgb<T>(&mut self, note: T, color: RgbColor) -> Result<(), HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        *self.pad_slot(note.into())? = PadLight::Rgb(color);
+        Ok(())
+    }
+
+    /// Sets the light of a pad of the grid, pads outside the grid are ignored
+    pub fn set_pad_light<L>(&mut self, pad: PadKey, light: L)
+    where
+        L: Into<PadLight>,
+    {
+        if let Ok(slot) = self.pad_slot(pad.into()) {
+            *slot = light.into();
+        }
+    }
+
+    /// Pads set with an RGB colour give the nearest palette colour, see `pad_light` for the exact one
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn pad<T>(&self, note: T) -> Result<PadLed, HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        self.pad_light(note).map(PadLight::to_palette)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn pad_light<T>(&self, note: T) -> Result<PadLight, HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        let note = note.into();
+        self.pads
+            .get(usize::from(note))
+            .copied()
+            .ok_or(HardwareMappingError::InvalidPadIndex(note))
+    }
+
+    pub fn fill_pads<M>(&mut self, led_mode: M, color: LedColor)
+    where
+        M: Into<LedMode>,
+    {
+        self.pads.fill(PadLight::Palette(PadLed {
+            mode: led_mode.into(),
+            color,
+        }));
+    }
+
+    pub fn set_button<T>(&mut self, button: T, state: ButtonLedState)
+    where
+        T: Into<LedButton>,
+    {
+        self.buttons[button.into().slot()] = state;
+    }
+
+    pub fn fill_buttons(&mut self, state: ButtonLedState) {
+        self.buttons.fill(state);
+    }
+
+    #[must_use]
+    pub fn button<T>(&self, button: T) -> ButtonLedState
+    where
+        T: Into<LedButton>,
+    {
+        self.buttons[button.into().slot()]
+    }
+
+    /// Turns every light off
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// MIDI messages needed to go from `previous` to this frame, every light when there is no previous frame.
+    /// Pads with an RGB colour are gathered in a single RGB message, with one range per run of adjacent pads of the same colour
+    #[must_use]
+    pub fn diff(&self, previous: Option<&Self>) -> Vec<Vec<u8>> {
+        let changed_pads = (0..PAD_COUNT)
+            .zip(self.pads.iter())
+            .filter(|(i, pad)| previous.is_none_or(|p| p.pads[usize::from(*i)] != **pad));
+        let rgb_pads = rgb_ranges(changed_pads.clone().filter_map(|(i, pad)| match pad {
+            PadLight::Rgb(color) => Some((i, *color)),
+            PadLight::Palette(_) => None,
+        }));
+        let pads = changed_pads
+            .filter_map(|(i, pad)| match pad {
+                PadLight::Palette(pad) => Some(vec![pad.mode.into(), i, pad.color.into()]),
+                PadLight::Rgb(_) => None,
+            })
+            .chain((!rgb_pads.is_empty()).then(|| rgb_sysex(&rgb_pads)));
+        let buttons = LedButton::ALL
+            .iter()
+            .zip(self.buttons.iter())
+            .enumerate()
+            .filter(|(slot, (_, state))| previous.is_none_or(|p| p.buttons[*slot] != **state))
+            .map(|(_, (button, state))| vec![BUTTON_LED_STATUS, (*button).into(), (*state).into()]);
+        pads.chain(buttons).collect()
+    }
+
+    fn pad_slot(&mut self, note: u8) -> Result<&mut PadLight, HardwareMappingError> {
+        self.pads
+            .get_mut(usize::from(note))
+            .ok_or(HardwareMappingError::InvalidPadIndex(note))
+    }
+}
+
+/// Ranges of the pads, merging adjacent notes of the same colour
+fn rgb_ranges<I>(pads: I) -> Vec<(RangeInclusive<u8>, RgbColor)>
+where
+    I: IntoIterator<Item = (u8, RgbColor)>,
+{
+    let mut ranges: Vec<(RangeInclusive<u8>, RgbColor)> = Vec::new();
+    for (note, color) in pads {
+        match ranges.last_mut() {
+            Some((range, last)) if *last == color && range.end().checked_add(1) == Some(note) => {
+                *range = *range.start()..=note;
+            }
+            _ => ranges.push((note..=note, color)),
+        }
+    }
+    ranges
+}
+
+/// Editable `LedFrame` that remembers what the device is showing.
+///
+/// Mutate it freely through `Deref`, then `flush` only transmits the lights that changed since the last flush
+/// ```Rust
+///let mut frame_buffer = FrameBuffer::default();
+///frame_buffer.fill_pads(LedMode::On100Percent, LedColor::Off);
+///frame_buffer.set_pad(LedMode::Blinking1over4, PadKey::Grid(1, 1), LedColor::Red)?;
+///frame_buffer.flush(&mut midi_out)?; // 40 messages, nothing was sent yet
+///frame_buffer.set_pad(LedMode::On100Percent, PadKey::Grid(1, 1), LedColor::Green)?;
+///frame_buffer.flush(&mut midi_out)?; // 1 message
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameBuffer {
+    current: LedFrame,
+    displayed: Option<LedFrame>,
+}
+
+impl FrameBuffer {
+    /// Sends the lights that changed since the last flush and returns how many messages were sent.
+    /// When a message fails, what the device shows is unknown and the buffer is invalidated
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn flush<O: MidiSink>(
+        &mut self,
+        output: &mut ChannelOutput<O>,
+    ) -> Result<usize, TransmissionError> {
+        let changes = self.current.diff(self.displayed.as_ref());
+        if let Err(e) = changes.iter().try_for_each(|led_msg| output.send(led_msg)) {
+            self.invalidate();
+            return Err(e);
+        }
+        self.displayed = Some(self.current.clone());
+        Ok(changes.len())
+    }
+
+    /// Forgets what the device is showing, the next flush will send every light
+    pub const fn invalidate(&mut self) {
+        self.displayed = None;
+    }
+
+    /// Frame shown by the device after the last successful flush
+    #[must_use]
+    pub const fn displayed(&self) -> Option<&LedFrame> {
+        self.displayed.as_ref()
+    }
+}
+
+impl Deref for FrameBuffer {
+    type Target = LedFrame;
+
+    fn deref(&self) -> &Self::Target {
+        &self.current
+    }
+}
+
+impl DerefMut for FrameBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameBuffer;
+    use crate::enums::button::led_button::LedButton;
+    use crate::enums::button::pads::{PAD_COUNT, PadKey};
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::io::output::{ChannelOutput, rgb_sysex};
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+
+    const EVERY_LIGHT: usize = PAD_COUNT as usize + LedButton::ALL.len();
+
+    #[test]
+    fn first_flush_sends_every_pad_and_button() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.set_pad(LedMode::On50Percent, PadKey::Grid(1, 1), LedColor::Red)?;
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, EVERY_LIGHT);
+        let received = device.received();
+        assert_eq!(received.len(), EVERY_LIGHT);
+        assert!(received.contains(&vec![0x92, 0x20, 0x05]));
+        assert!(received.contains(&vec![0x96, 0x00, 0x00]));
+        assert!(received.contains(&vec![0x90, u8::from(SoftKey::Mute), 0x00]));
+        Ok(())
+    }
+
+    #[test]
+    fn later_flushes_send_only_the_changes() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.flush(&mut midi_out)?;
+        let _ = device.take_received();
+        frame_buffer.set_pad(LedMode::On100Percent, 39, LedColor::Green)?;
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, 1);
+        assert_eq!(device.take_received(), vec![vec![0x96, 0x27, 0x15]]);
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, 0);
+        frame_buffer.set_button(SoftKey::Mute, ButtonLedState::Blinking);
+        frame_buffer.set_button(SoftKey::Mute, ButtonLedState::Off);
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, 0);
+        assert!(device.received().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn adjacent_rgb_pads_of_the_same_colour_share_a_range() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.set_pad_rgb(0, RgbColor::WHITE)?;
+        frame_buffer.flush(&mut midi_out)?;
+        let _ = device.take_received();
+        let orange = RgbColor::new(255, 128, 0);
+        let blue = RgbColor::new(0, 0, 255);
+        frame_buffer.set_pad_rgb(3, orange)?;
+        frame_buffer.set_pad_rgb(4, orange)?;
+        frame_buffer.set_pad(LedMode::On100Percent, 5, LedColor::Red)?;
+        frame_buffer.set_pad_rgb(6, orange)?;
+        frame_buffer.set_pad_rgb(7, blue)?;
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, 2);
+        assert_eq!(
+            device.take_received(),
+            vec![
+                vec![0x96, 0x05, 0x05],
+                rgb_sysex(&[(3..=4, orange), (6..=6, orange), (7..=7, blue)])
+            ]
+        );
+
+        for note in 0..PAD_COUNT {
+            frame_buffer.set_pad_rgb(note, blue)?;
+        }
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, 1);
+        assert_eq!(
+            device.received(),
+            vec![rgb_sysex(&[(0..=6, blue), (8..=39, blue)])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_flush_invalidates_the_buffer() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.flush(&mut midi_out)?;
+        frame_buffer.set_pad(LedMode::On100Percent, 0, LedColor::Red)?;
+        device.unplug();
+        assert!(frame_buffer.flush(&mut midi_out).is_err());
+        assert!(frame_buffer.displayed().is_none());
+        device.plug_in();
+        let _ = device.take_received();
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, EVERY_LIGHT);
+        assert_eq!(device.received().len(), EVERY_LIGHT);
+        Ok(())
+    }
+
+    #[test]
+    fn invalidate_resends_every_light() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.flush(&mut midi_out)?;
+        frame_buffer.invalidate();
+        assert_eq!(frame_buffer.flush(&mut midi_out)?, EVERY_LIGHT);
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use midi_msg::{
+    IdentityReply, ManufacturerID, MidiMsg, SystemExclusiveMsg, UniversalNonRealTimeMsg,
+};
+
+pub const AKAI_MANUFACTURER_ID: ManufacturerID = ManufacturerID(0x47, None);
+pub const APC_KEY_25_MK2_PRODUCT_ID: u16 = 0x4E;
+/// Product ID of the APC40 mkII. The LED initialization message is addressed to it, not to the mk2 itself,
+/// the APC Key 25 mk2 still reports `APC_KEY_25_MK2_PRODUCT_ID` when identifying
+pub const APC40_MK2_PRODUCT_ID: u16 = 0x29;
+
+/// Universal Device Inquiry, answered by any device listening on the port
+pub const DEVICE_INQUIRY_REQUEST: [u8; 6] = [
+    0xF0, // SysEx start
+    0x7E, // Universal non real time
+    0x7F, // Device ID (all devices)
+    0x06, // General information
+    0x01, // Identity request
+    0xF7, // SysEx end
+];
+
+/// Identity of the connected controller, as returned by the universal Device Inquiry
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub manufacturer: ManufacturerID,
+    /// Akai reports the product ID here
+    pub family: u16,
+    pub model: u16,
+    pub firmware_version: [u8; 4],
+}
+
+impl DeviceIdentity {
+    #[must_use]
+    pub fn is_akai(&self) -> bool {
+        self.manufacturer == AKAI_MANUFACTURER_ID
+    }
+
+    #[must_use]
+    pub fn is_apc_key_25_mk2(&self) -> bool {
+        self.is_akai() && self.family == APC_KEY_25_MK2_PRODUCT_ID
+    }
+
+    /// Extracts the identity from a Device Inquiry reply, any other message is ignored
+    #[must_use]
+    pub fn from_midi_msg(msg: &MidiMsg) -> Option<Self> {
+        match msg {
+            MidiMsg::SystemExclusive {
+                msg:
+                    SystemExclusiveMsg::UniversalNonRealTime {
+                        msg: UniversalNonRealTimeMsg::IdentityReply(reply),
+                        ..
+                    },
+            } => Some((*reply).into()),
+            _ => None,
+        }
+    }
+}
+
+impl From<IdentityReply> for DeviceIdentity {
+    fn from(reply: IdentityReply) -> Self {
+        Self {
+            manufacturer: reply.id,
+            family: reply.family,
+            model: reply.family_member,
+            firmware_version: reply.software_revision.into(),
+        }
+    }
+}
@@ -1,58 +1,72 @@
 use crate::enums::input_group::ChannelKind;
 use crate::enums::message_filter::MessageFilter;
-use crate::errors::io::ChannelCreationError;
+use crate::enums::port_selector::PortSelector;
+use crate::errors::io::{ChannelCreationError, DeviceInquiryError};
 use crate::io::channel::Channel;
+use crate::io::identity::DeviceIdentity;
 use crate::io::input_data::MidiInputData;
+use crate::io::output::ChannelOutput;
+use crate::io::transport::hardware::{Midir, MidirInput};
+use crate::io::transport::{MidiBackend, MidiSink, MidiSource};
 use bon::bon;
 use midi_msg::{MidiMsg, ReceiverContext};
-use midir::{MidiInput, MidiInputConnection, MidiInputPort};
+use midir::MidiInput;
+use std::sync::mpsc;
+use std::time::Duration;
 
-pub struct InputChannel {
-    midi_input: MidiInput,
-    input_port: MidiInputPort,
+pub struct InputChannel<S = MidirInput> {
+    source: S,
 }
 
 #[bon]
 impl InputChannel {
+    /// Opens the input port given by `port` or, when missing, by `port_selector`.
+    /// By default, the first port that looks like an APC Key 25 is used
     #[builder]
     pub fn new(
         port: Option<usize>,
+        port_selector: Option<PortSelector>,
         msg_to_ignore: Option<MessageFilter>,
     ) -> Result<Self, ChannelCreationError> {
-        let mut midi_in = Self::get_midi()?;
-        midi_in.ignore(msg_to_ignore.unwrap_or(MessageFilter::None).into());
-        let in_ports = midi_in.ports();
-        let chosen_port = port.unwrap_or(2);
-        Ok(Self {
-            midi_input: midi_in,
-            input_port: in_ports
-                .get(chosen_port)
-                .ok_or(ChannelCreationError::PortOutOfRange(chosen_port))?
-                .clone(),
-        })
+        let selector = port.map_or_else(|| port_selector.unwrap_or_default(), PortSelector::Index);
+        Ok(Self::from_source(Midir.open_source(
+            &selector,
+            msg_to_ignore.unwrap_or(MessageFilter::None),
+        )?))
+    }
+}
+
+impl<S: MidiSource> InputChannel<S> {
+    /// Builds an input channel on top of any transport, e.g. a `LoopbackDevice` input when there is no hardware at hand
+    #[must_use]
+    pub const fn from_source(source: S) -> Self {
+        Self { source }
     }
 
     /// Very biased listener method. It will try to decode the MIDI messages to a high level representation
-    /// and after that it will call the given closure
+    /// and after that it will call the given closure.
+    ///
+    /// `data` is moved into the returned connection, so it must be `Send + 'static`: share state with an `Arc` rather
+    /// than a borrow
     /// ```Rust
     ///let midi_in = ramidier::io::input::InputChannel::builder().build()?;
     ///let _conn_in = midi_in.listen(
     ///    Some("midir-input"),
     ///    move |stamp, received_input, data| listener_logic(&mut midi_out, stamp, &received_input, data),
     ///    MyDataStruct{}, // could also be () it there is no need for data
-    ///    PadsAndKnobsChannel, // could also be KeyboardChannel depending on your needs
+    ///    PadsAndKnobsChannel, // could also be KeyboardChannel or CombinedChannel depending on your needs
     /// )?;
     /// ```
     /// # Errors
     ///
     /// Will return `ChannelCreationError` if there are low-level issues communicating with the device
-    pub fn listen<F, T: Send, C>(
+    pub fn listen<F, T: Send + 'static, C>(
         self,
         port_name: Option<&str>,
         mut input_handler_callback: F,
         data: T,
         _channel_type: C, // type inference to avoid awkward turbofish syntax on caller
-    ) -> Result<MidiInputConnection<T>, ChannelCreationError>
+    ) -> Result<S::Connection<T>, ChannelCreationError>
     where
         C: ChannelKind + Send + 'static,
         F: FnMut(u64, MidiInputData<C::Group>, &mut T) + Send + 'static,
@@ -69,8 +83,44 @@ impl InputChannel {
         self.listen_raw(port_name, wrapper, data)
     }
 
+    /// Sends a universal Device Inquiry through the given output and waits for the reply.
+    /// The input channel is consumed, build a new one to keep listening afterward
+    /// ```Rust
+    ///let midi_in = ramidier::io::input::InputChannel::builder().build()?;
+    ///let identity = midi_in.identify(&mut midi_out, Duration::from_millis(500))?;
+    ///if !identity.is_apc_key_25_mk2() {
+    ///    println!("Unsupported device {identity:?}");
+    ///}
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `DeviceInquiryError` if the request cannot be sent or no reply arrives before the timeout
+    pub fn identify<O: MidiSink>(
+        self,
+        output: &mut ChannelOutput<O>,
+        timeout: Duration,
+    ) -> Result<DeviceIdentity, DeviceInquiryError> {
+        let (tx, rx) = mpsc::sync_channel(1);
+        let _conn_in = self
+            .listen_midi_msg(
+                Some("akai-midir-device-inquiry"),
+                move |_, msg, ()| {
+                    if let Some(identity) = DeviceIdentity::from_midi_msg(&msg) {
+                        let _ = tx.try_send(identity);
+                    }
+                },
+                (),
+            )
+            .map_err(|e| DeviceInquiryError::Listening { source: e })?;
+        output
+            .request_device_identity()
+            .map_err(|e| DeviceInquiryError::Request { source: e })?;
+        rx.recv_timeout(timeout)
+            .map_err(|_| DeviceInquiryError::Timeout(timeout))
+    }
+
     /// Listener method that will try to decode the received bytes to the MIDI messages
-    /// and after that it will call the given closure
+    /// and after that it will call the given closure. `data` must be `Send + 'static`, as for `listen`
     /// ```Rust
     ///let midi_in = ramidier::io::input::InputChannel::builder().build()?;
     ///let _conn_in = midi_in.listen_midi_msg(
@@ -82,12 +132,12 @@ impl InputChannel {
     /// # Errors
     ///
     /// Will return `ChannelCreationError` if there are low-level issues communicating with the device
-    pub fn listen_midi_msg<F, T: Send>(
+    pub fn listen_midi_msg<F, T: Send + 'static>(
         self,
         port_name: Option<&str>,
         mut input_handler_callback: F,
         data: T,
-    ) -> Result<MidiInputConnection<T>, ChannelCreationError>
+    ) -> Result<S::Connection<T>, ChannelCreationError>
     where
         F: FnMut(u64, MidiMsg, &mut T) + Send + 'static,
     {
@@ -101,6 +151,7 @@ impl InputChannel {
     }
 
     /// Listener method that will will call the given closure every time it receives a midi message. It does not decode the raw bytes.
+    /// `data` must be `Send + 'static`, as for `listen`
     /// ```Rust
     ///let midi_in = ramidier::io::input::InputChannel::builder().build()?;
     ///let _conn_in = midi_in.listen_raw(
@@ -112,23 +163,20 @@ impl InputChannel {
     /// # Errors
     ///
     /// Will return `ChannelCreationError` if there are low-level issues communicating with the device
-    pub fn listen_raw<F, T: Send>(
+    pub fn listen_raw<F, T: Send + 'static>(
         self,
         port_name: Option<&str>,
         input_handler_callback: F,
         data: T,
-    ) -> Result<MidiInputConnection<T>, ChannelCreationError>
+    ) -> Result<S::Connection<T>, ChannelCreationError>
     where
         F: FnMut(u64, &[u8], &mut T) + Send + 'static,
     {
-        self.midi_input
-            .connect(
-                &self.input_port,
-                port_name.unwrap_or("akai-midir-read-input"),
-                input_handler_callback,
-                data,
-            )
-            .map_err(|e| ChannelCreationError::EstablishingInputConnection { source: e })
+        self.source.connect(
+            port_name.unwrap_or("akai-midir-read-input"),
+            input_handler_callback,
+            data,
+        )
     }
 }
 
@@ -139,3 +187,59 @@ impl Channel for InputChannel {
             .map_err(|e| ChannelCreationError::InitializingChannel { source: e })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::InputChannel;
+    use crate::errors::io::{DeviceInquiryError, TransmissionError};
+    use crate::io::identity::{AKAI_MANUFACTURER_ID, DEVICE_INQUIRY_REQUEST};
+    use crate::io::output::ChannelOutput;
+    use crate::io::transport::MidiSink;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::time::Duration;
+
+    /// Identity Reply of an APC Key 25 mk2 with firmware 1.2.3.4
+    const IDENTITY_REPLY: [u8; 15] = [
+        0xF0, 0x7E, 0x7F, 0x06, 0x02, 0x47, 0x4E, 0x00, 0x19, 0x00, 0x01, 0x02, 0x03, 0x04, 0xF7,
+    ];
+
+    /// Answers the Device Inquiry the way the controller does
+    struct Responder(LoopbackDevice);
+
+    impl MidiSink for Responder {
+        fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError> {
+            if data == DEVICE_INQUIRY_REQUEST {
+                self.0.send(&IDENTITY_REPLY);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn identify_decodes_the_identity_reply() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(Responder(device.clone()));
+        let identity = InputChannel::from_source(device.input())
+            .identify(&mut midi_out, Duration::from_secs(1))?;
+        assert_eq!(identity.manufacturer, AKAI_MANUFACTURER_ID);
+        assert_eq!(identity.family, 0x4E);
+        assert_eq!(identity.model, 0x19);
+        assert_eq!(identity.firmware_version, [1, 2, 3, 4]);
+        assert!(identity.is_apc_key_25_mk2());
+        assert_eq!(device.listener_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn identify_times_out_without_a_reply() {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let timeout = Duration::from_millis(10);
+        assert!(matches!(
+            InputChannel::from_source(device.input()).identify(&mut midi_out, timeout),
+            Err(DeviceInquiryError::Timeout(t)) if t == timeout
+        ));
+        assert_eq!(device.received(), vec![DEVICE_INQUIRY_REQUEST.to_vec()]);
+    }
+}
@@ -1,8 +1,30 @@
 use midi_msg::Channel;
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputAction {
+    /// Note on, `velocity` is always 127 for buttons without velocity sensitivity
+    Pressed { velocity: u8 },
+    /// Note off, or note on with velocity 0
+    Released { velocity: u8 },
+    /// Control change, as sent by the knobs
+    Changed { value: u8 },
+}
+
+#[derive(Debug, Copy, Clone)]
 pub struct MidiInputData<C> {
     pub channel: Channel,
     pub input_group: C,
-    pub value: u8,
+    pub action: InputAction,
+}
+
+impl<C> MidiInputData<C> {
+    #[must_use]
+    pub const fn is_pressed(&self) -> bool {
+        matches!(self.action, InputAction::Pressed { .. })
+    }
+
+    #[must_use]
+    pub const fn is_released(&self) -> bool {
+        matches!(self.action, InputAction::Released { .. })
+    }
 }
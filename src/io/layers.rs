@@ -0,0 +1,422 @@
+use crate::enums::button::led_button::LedButton;
+use crate::enums::button::pads::PAD_COUNT;
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::errors::io::TransmissionError;
+use crate::errors::led::LedError;
+use crate::errors::mapping::HardwareMappingError;
+use crate::io::frame::{FrameBuffer, LedFrame, PadLed};
+use crate::io::output::ChannelOutput;
+use crate::io::transport::MidiSink;
+
+/// Lights set by one part of an application, anything not set is transparent and shows the layers below
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedLayer {
+    pads: [Option<PadLed>; PAD_COUNT as usize],
+    buttons: [Option<ButtonLedState>; LedButton::ALL.len()],
+    visible: bool,
+}
+
+impl Default for LedLayer {
+    fn default() -> Self {
+        Self {
+            pads: [None; PAD_COUNT as usize],
+            buttons: [None; LedButton::ALL.len()],
+            visible: true,
+        }
+    }
+}
+
+impl LedLayer {
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn set_pad<M, T>(
+        &mut self,
+        led_mode: M,
+        note: T,
+        color: LedColor,
+    ) -> Result<(), HardwareMappingError>
+    where
+        M: Into<LedMode>,
+        T: Into<u8>,
+    {
+        *self.pad_slot(note.into())? = Some(PadLed {
+            mode: led_mode.into(),
+            color,
+        });
+        Ok(())
+    }
+
+    /// Makes the pad transparent again
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn clear_pad<T>(&mut self, note: T) -> Result<(), HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        *self.pad_slot(note.into())? = None;
+        Ok(())
+    }
+
+    /// `None` when the pad is transparent
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn pad<T>(&self, note: T) -> Result<Option<PadLed>, HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        let note = note.into();
+        self.pads
+            .get(usize::from(note))
+            .copied()
+            .ok_or(HardwareMappingError::InvalidPadIndex(note))
+    }
+
+    pub fn fill_pads<M>(&mut self, led_mode: M, color: LedColor)
+    where
+        M: Into<LedMode>,
+    {
+        self.pads.fill(Some(PadLed {
+            mode: led_mode.into(),
+            color,
+        }));
+    }
+
+    pub fn set_button<T>(&mut self, button: T, state: ButtonLedState)
+    where
+        T: Into<LedButton>,
+    {
+        self.buttons[button.into().slot()] = Some(state);
+    }
+
+    /// Makes the button transparent again
+    pub fn clear_button<T>(&mut self, button: T)
+    where
+        T: Into<LedButton>,
+    {
+        self.buttons[button.into().slot()] = None;
+    }
+
+    /// `None` when the button is transparent
+    #[must_use]
+    pub fn button<T>(&self, button: T) -> Option<ButtonLedState>
+    where
+        T: Into<LedButton>,
+    {
+        self.buttons[button.into().slot()]
+    }
+
+    /// Makes every light transparent
+    pub fn clear(&mut self) {
+        self.pads.fill(None);
+        self.buttons.fill(None);
+    }
+
+    #[must_use]
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// A hidden layer keeps its lights but is skipped when compositing
+    pub const fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Draws the opaque lights of the layer over the frame
+    pub fn draw_over(&self, frame: &mut LedFrame) {
+        for (note, pad) in (0..PAD_COUNT).zip(self.pads) {
+            if let Some(pad) = pad {
+                let _ = frame.set_pad(pad.mode, note, pad.color);
+            }
+        }
+        for (button, state) in LedButton::ALL.into_iter().zip(self.buttons) {
+            if let Some(state) = state {
+                frame.set_button(button, state);
+            }
+        }
+    }
+
+    fn pad_slot(&mut self, note: u8) -> Result<&mut Option<PadLed>, HardwareMappingError> {
+        self.pads
+            .get_mut(usize::from(note))
+            .ok_or(HardwareMappingError::InvalidPadIndex(note))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NamedLayer {
+    name: String,
+    z_order: i32,
+    layer: LedLayer,
+}
+
+/// Named `LedLayer`s composited by z-order, higher layers hide the lower ones wherever they are opaque.
+///
+/// Each part of an application draws on its own layer, removing or clearing an overlay brings back what was below it.
+/// Layers with the same z-order stack in the order they were added. `flush` only sends the lights of the composite
+/// that changed since the previous flush
+/// ```Rust
+///let mut layers = LayerStack::default();
+///layers.add_layer("base", 0).fill_pads(LedMode::On50Percent, LedColor::Blue1);
+///layers.add_layer("error", 10).set_pad(LedMode::Blinking1over4, PadKey::Grid(1, 1), LedColor::Red)?;
+///layers.flush(&mut midi_out)?;
+///layers.remove_layer("error");
+///layers.flush(&mut midi_out)?; // Grid(1, 1) is blue again
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LayerStack {
+    /// Sorted by z-order, from the bottom layer up
+    layers: Vec<NamedLayer>,
+    frame_buffer: FrameBuffer,
+}
+
+impl LayerStack {
+    /// Adds an empty, fully transparent layer, or returns the existing layer with that name untouched
+    pub fn add_layer<N>(&mut self, name: N, z_order: i32) -> &mut LedLayer
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        let index = self.position(&name).unwrap_or_else(|| {
+            self.insert(NamedLayer {
+                name,
+                z_order,
+                layer: LedLayer::default(),
+            })
+        });
+        &mut self.layers[index].layer
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<LedLayer> {
+        self.position(name)
+            .map(|index| self.layers.remove(index).layer)
+    }
+
+    #[must_use]
+    pub fn layer(&self, name: &str) -> Option<&LedLayer> {
+        self.position(name).map(|index| &self.layers[index].layer)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut LedLayer> {
+        self.position(name)
+            .map(|index| &mut self.layers[index].layer)
+    }
+
+    /// Moves the layer above or below the others, it goes on top of the layers with the same z-order
+    /// # Errors
+    ///
+    /// Will return `LedError` if there is no layer with that name
+    pub fn set_z_order(&mut self, name: &str, z_order: i32) -> Result<(), LedError> {
+        let index = self
+            .position(name)
+            .ok_or_else(|| LedError::UnknownLayer(name.to_owned()))?;
+        let mut layer = self.layers.remove(index);
+        layer.z_order = z_order;
+        self.insert(layer);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn z_order(&self, name: &str) -> Option<i32> {
+        self.position(name).map(|index| self.layers[index].z_order)
+    }
+
+    /// Layer names from the bottom up
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|layer| layer.name.as_str())
+    }
+
+    /// What the device shows: every visible layer drawn from the bottom up over a dark frame
+    #[must_use]
+    pub fn composite(&self) -> LedFrame {
+        let mut frame = LedFrame::default();
+        for named in self.layers.iter().filter(|named| named.layer.is_visible()) {
+            named.layer.draw_over(&mut frame);
+        }
+        frame
+    }
+
+    /// Sends the lights of the composite that changed since the last flush and returns how many messages were sent
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn flush<O: MidiSink>(
+        &mut self,
+        output: &mut ChannelOutput<O>,
+    ) -> Result<usize, TransmissionError> {
+        let frame = self.composite();
+        *self.frame_buffer = frame;
+        self.frame_buffer.flush(output)
+    }
+
+    /// Forgets what the device is showing, the next flush will send every light
+    pub const fn invalidate(&mut self) {
+        self.frame_buffer.invalidate();
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    fn insert(&mut self, layer: NamedLayer) -> usize {
+        let index = self
+            .layers
+            .partition_point(|other| other.z_order <= layer.z_order);
+        self.layers.insert(index, layer);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LayerStack;
+    use crate::enums::button::led_button::LedButton;
+    use crate::enums::button::pads::{PAD_COUNT, PadKey};
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::io::frame::PadLed;
+    use crate::io::output::ChannelOutput;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+
+    const fn pad(mode: LedMode, color: LedColor) -> PadLed {
+        PadLed { mode, color }
+    }
+
+    /// Blue background, a red overlay and a green one stacked on it at the same z-order
+    fn stack() -> Result<LayerStack, Box<dyn Error>> {
+        let mut layers = LayerStack::default();
+        layers.add_layer("green", 10).set_pad(
+            LedMode::On100Percent,
+            PadKey::Grid(1, 1),
+            LedColor::Green,
+        )?;
+        let red = layers.add_layer("red", 10);
+        red.set_pad(LedMode::On100Percent, PadKey::Grid(1, 1), LedColor::Red)?;
+        red.set_pad(LedMode::On100Percent, PadKey::Grid(1, 2), LedColor::Red)?;
+        let base = layers.add_layer("base", 0);
+        base.fill_pads(LedMode::On50Percent, LedColor::Blue1);
+        base.set_button(SoftKey::Mute, ButtonLedState::On);
+        Ok(layers)
+    }
+
+    #[test]
+    fn layers_are_sorted_by_z_order() -> Result<(), Box<dyn Error>> {
+        let mut layers = stack()?;
+        layers.add_layer("top", 20);
+        layers.add_layer("bottom", -5);
+        assert_eq!(
+            layers.names().collect::<Vec<_>>(),
+            vec!["bottom", "base", "green", "red", "top"]
+        );
+        layers.set_z_order("green", 10)?;
+        assert_eq!(
+            layers.names().collect::<Vec<_>>(),
+            vec!["bottom", "base", "red", "green", "top"]
+        );
+        assert!(layers.set_z_order("missing", 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn opaque_lights_hide_the_layers_below() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut layers = stack()?;
+        let composite = layers.composite();
+        // Added last at the same z-order, red stacks on top of green
+        assert_eq!(
+            composite.pad(PadKey::Grid(1, 1))?,
+            pad(LedMode::On100Percent, LedColor::Red)
+        );
+        assert_eq!(
+            composite.pad(PadKey::Grid(5, 8))?,
+            pad(LedMode::On50Percent, LedColor::Blue1)
+        );
+        assert_eq!(composite.button(SoftKey::Mute), ButtonLedState::On);
+
+        assert_eq!(
+            layers.flush(&mut midi_out)?,
+            usize::from(PAD_COUNT) + LedButton::ALL.len()
+        );
+        let received = device.take_received();
+        assert!(received.contains(&vec![0x96, 0x20, 0x05]));
+        assert!(received.contains(&vec![0x96, 0x21, 0x05]));
+        assert!(received.contains(&vec![0x92, 0x22, 0x29]));
+        assert!(received.contains(&vec![0x90, u8::from(SoftKey::Mute), 0x01]));
+        assert_eq!(layers.flush(&mut midi_out)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_layers_show_what_is_below() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut layers = stack()?;
+        layers.flush(&mut midi_out)?;
+        let _ = device.take_received();
+
+        layers
+            .layer_mut("red")
+            .ok_or("no red layer")?
+            .set_visible(false);
+        assert_eq!(
+            layers.composite().pad(PadKey::Grid(1, 2))?,
+            pad(LedMode::On50Percent, LedColor::Blue1)
+        );
+        assert_eq!(layers.flush(&mut midi_out)?, 2);
+        assert_eq!(
+            device.take_received(),
+            vec![vec![0x96, 0x20, 0x15], vec![0x92, 0x21, 0x29]]
+        );
+
+        layers
+            .layer_mut("red")
+            .ok_or("no red layer")?
+            .set_visible(true);
+        assert_eq!(layers.flush(&mut midi_out)?, 2);
+        assert_eq!(
+            device.take_received(),
+            vec![vec![0x96, 0x20, 0x05], vec![0x96, 0x21, 0x05]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn removing_a_layer_restores_what_was_below() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut layers = stack()?;
+        layers.flush(&mut midi_out)?;
+        let _ = device.take_received();
+
+        assert!(layers.remove_layer("red").is_some());
+        assert_eq!(
+            layers.composite().pad(PadKey::Grid(1, 1))?,
+            pad(LedMode::On100Percent, LedColor::Green)
+        );
+        assert_eq!(layers.flush(&mut midi_out)?, 2);
+        assert_eq!(
+            device.take_received(),
+            vec![vec![0x96, 0x20, 0x15], vec![0x92, 0x21, 0x29]]
+        );
+
+        assert!(layers.remove_layer("green").is_some());
+        assert!(layers.remove_layer("green").is_none());
+        assert_eq!(layers.flush(&mut midi_out)?, 1);
+        assert_eq!(device.take_received(), vec![vec![0x92, 0x20, 0x29]]);
+
+        assert!(layers.remove_layer("base").is_some());
+        assert_eq!(
+            layers.composite().button(SoftKey::Mute),
+            ButtonLedState::Off
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,20 @@
+#[cfg(feature = "embedded-graphics")]
+pub mod canvas;
 mod channel;
+pub mod device;
+pub mod frame;
+pub mod identity;
 pub mod input;
 pub mod input_data;
+pub mod layers;
+pub mod notification;
 pub mod output;
+mod queue;
+pub mod receiver;
+pub mod shift_layer;
+pub mod state;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod supervisor;
+pub(crate) mod sync;
+pub mod transport;
@@ -0,0 +1,323 @@
+use crate::enums::button::pads::{GRID_COLUMNS, PadKey};
+use crate::enums::led_light::behavior::{Brightness, LedBehavior};
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::errors::io::TransmissionError;
+use crate::io::frame::{LedFrame, PadLed};
+use crate::io::output::ChannelOutput;
+use crate::io::sync::lock;
+use crate::io::transport::MidiSink;
+use bon::bon;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Brightness levels of a pulse, up and back down
+const PULSE_LEVELS: [Brightness; 13] = [
+    Brightness::Percent10,
+    Brightness::Percent25,
+    Brightness::Percent50,
+    Brightness::Percent65,
+    Brightness::Percent75,
+    Brightness::Percent90,
+    Brightness::Percent100,
+    Brightness::Percent90,
+    Brightness::Percent75,
+    Brightness::Percent65,
+    Brightness::Percent50,
+    Brightness::Percent25,
+    Brightness::Percent10,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NotificationPattern {
+    /// The whole grid turns to the colour and back, `count` times
+    Flash { count: u8 },
+    /// The whole grid fades in and out through the brightness levels
+    Pulse,
+    /// A column of the colour crosses the grid from left to right
+    Sweep,
+}
+
+/// Short pattern shown over whatever the pads display, which comes back once the pattern is over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pattern: NotificationPattern,
+    color: LedColor,
+    step: Duration,
+    buttons: bool,
+}
+
+#[bon]
+impl Notification {
+    /// `step` is how long every step of the pattern lasts, 100 ms by default.
+    /// With `buttons`, the button LEDs are lit for the whole pattern as well
+    #[builder]
+    pub fn new(
+        pattern: NotificationPattern,
+        color: LedColor,
+        step: Option<Duration>,
+        buttons: Option<bool>,
+    ) -> Self {
+        Self {
+            pattern,
+            color,
+            step: step.unwrap_or(Duration::from_millis(100)),
+            buttons: buttons.unwrap_or(false),
+        }
+    }
+}
+
+impl Notification {
+    /// Three red flashes, buttons included
+    #[must_use]
+    pub fn error() -> Self {
+        Self::builder()
+            .pattern(NotificationPattern::Flash { count: 3 })
+            .color(LedColor::Red)
+            .buttons(true)
+            .build()
+    }
+
+    /// A green pulse
+    #[must_use]
+    pub fn success() -> Self {
+        Self::builder()
+            .pattern(NotificationPattern::Pulse)
+            .color(LedColor::Green)
+            .step(Duration::from_millis(40))
+            .build()
+    }
+
+    #[must_use]
+    pub const fn step(&self) -> Duration {
+        self.step
+    }
+
+    #[must_use]
+    pub fn step_count(&self) -> usize {
+        match self.pattern {
+            NotificationPattern::Flash { count } => usize::from(count) * 2,
+            NotificationPattern::Pulse => PULSE_LEVELS.len(),
+            NotificationPattern::Sweep => usize::from(GRID_COLUMNS),
+        }
+    }
+
+    /// What the lights look like at the given step, drawn over `base`
+    #[must_use]
+    pub fn render(&self, step: usize, base: &LedFrame) -> LedFrame {
+        let mut frame = base.clone();
+        match self.pattern {
+            NotificationPattern::Flash { .. } if step % 2 == 1 => (),
+            NotificationPattern::Flash { .. } => frame.fill_pads(LedMode::On100Percent, self.color),
+            NotificationPattern::Pulse => frame.fill_pads(
+                LedBehavior::solid(PULSE_LEVELS[step % PULSE_LEVELS.len()]),
+                self.color,
+            ),
+            NotificationPattern::Sweep => {
+                let column = (step % usize::from(GRID_COLUMNS)) as u8 + 1;
+                for pad in PadKey::column(column) {
+                    frame.set_pad_light(
+                        pad,
+                        PadLed {
+                            mode: LedMode::On100Percent,
+                            color: self.color,
+                        },
+                    );
+                }
+            }
+        }
+        if self.buttons {
+            frame.fill_buttons(ButtonLedState::On);
+        }
+        frame
+    }
+
+    /// Plays the notification on its own thread, locking the output only while a step is sent.
+    /// LEDs set through the output in the meantime are the ones restored at the end
+    pub fn spawn<O>(
+        self,
+        output: Arc<Mutex<ChannelOutput<O>>>,
+    ) -> JoinHandle<Result<(), TransmissionError>>
+    where
+        O: MidiSink + Send + 'static,
+    {
+        thread::spawn(move || {
+            let mut shown = lock(&output).leds().clone();
+            for step in 0..self.step_count() {
+                {
+                    let mut output = lock(&output);
+                    let frame = self.render(step, output.leds());
+                    output.show_unrecorded(&frame, &mut shown)?;
+                }
+                thread::sleep(self.step);
+            }
+            let mut output = lock(&output);
+            let leds = output.leds().clone();
+            output.show_unrecorded(&leds, &mut shown)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Notification, NotificationPattern};
+    use crate::enums::button::led_button::LedButton;
+    use crate::enums::button::pads::{GRID_COLUMNS, PadKey};
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::io::frame::{LedFrame, PadLed, PadLight};
+    use crate::io::output::ChannelOutput;
+    use crate::io::sync::lock;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn flash(count: u8) -> Notification {
+        Notification::builder()
+            .pattern(NotificationPattern::Flash { count })
+            .color(LedColor::Red)
+            .step(Duration::ZERO)
+            .buttons(true)
+            .build()
+    }
+
+    #[test]
+    fn buttons_stay_lit_for_the_whole_pattern() {
+        let notification = flash(2);
+        let base = LedFrame::new();
+        for step in 0..notification.step_count() {
+            let frame = notification.render(step, &base);
+            for button in LedButton::ALL {
+                assert_eq!(frame.button(button), ButtonLedState::On);
+            }
+        }
+    }
+
+    #[test]
+    fn pulse_goes_up_then_down_in_brightness() -> Result<(), Box<dyn Error>> {
+        let notification = Notification::success();
+        let base = LedFrame::new();
+        let modes = (0..notification.step_count())
+            .map(|step| {
+                let frame = notification.render(step, &base);
+                assert!(
+                    PadKey::all()
+                        .all(|pad| frame.pad(pad).is_ok_and(|led| led.color == LedColor::Green))
+                );
+                frame.pad(PadKey::Grid(3, 4)).map(|led| led.mode)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            modes,
+            vec![
+                LedMode::On10Percent,
+                LedMode::On25Percent,
+                LedMode::On50Percent,
+                LedMode::On65Percent,
+                LedMode::On75Percent,
+                LedMode::On90Percent,
+                LedMode::On100Percent,
+                LedMode::On90Percent,
+                LedMode::On75Percent,
+                LedMode::On65Percent,
+                LedMode::On50Percent,
+                LedMode::On25Percent,
+                LedMode::On10Percent,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sweep_crosses_the_grid_from_left_to_right() -> Result<(), Box<dyn Error>> {
+        let notification = Notification::builder()
+            .pattern(NotificationPattern::Sweep)
+            .color(LedColor::Red)
+            .build();
+        let mut base = LedFrame::new();
+        base.fill_pads(LedMode::On50Percent, LedColor::Blue1);
+        assert_eq!(notification.step_count(), usize::from(GRID_COLUMNS));
+        let red = PadLed {
+            mode: LedMode::On100Percent,
+            color: LedColor::Red,
+        };
+        for (step, column) in [(0, 1), (usize::from(GRID_COLUMNS) - 1, GRID_COLUMNS)] {
+            let frame = notification.render(step, &base);
+            for pad in PadKey::all() {
+                let PadKey::Grid(_, pad_column) = pad;
+                let expected = if pad_column == column {
+                    red
+                } else {
+                    base.pad(pad)?
+                };
+                assert_eq!(frame.pad(pad)?, expected, "{pad:?} at step {step}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn spawn_restores_the_leds_set_during_the_pattern() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let output = Arc::new(Mutex::new(ChannelOutput::from_sink(device.output())));
+        lock(&output).set_pad_led(LedMode::On50Percent, 0, LedColor::Blue1)?;
+        let notification = Notification::builder()
+            .pattern(NotificationPattern::Sweep)
+            .color(LedColor::Red)
+            .step(Duration::from_millis(50))
+            .build();
+        let player = notification.spawn(Arc::clone(&output));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while device.received().len() < 2 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        lock(&output).set_pad_led(LedMode::On100Percent, 39, LedColor::Green)?;
+        player.join().map_err(|_| "notification panicked")??;
+
+        let mut shown = LedFrame::new();
+        for msg in device.received() {
+            assert!(shown.apply_message(&msg));
+        }
+        assert_eq!(&shown, lock(&output).leds());
+        assert_eq!(
+            shown.pad(0)?,
+            PadLed {
+                mode: LedMode::On50Percent,
+                color: LedColor::Blue1
+            }
+        );
+        assert_eq!(
+            shown.pad(39)?,
+            PadLed {
+                mode: LedMode::On100Percent,
+                color: LedColor::Green
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rgb_pads_are_restored_after_the_pattern() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let orange = RgbColor::new(255, 128, 0);
+        midi_out.set_pad_rgb(3, orange)?;
+        midi_out.set_pad_led(LedMode::Pulsing1over4, 4, LedColor::Blue1)?;
+        let before = midi_out.leds().clone();
+        midi_out.notify(&flash(1))?;
+        let mut shown = LedFrame::new();
+        for msg in device.take_received() {
+            assert!(shown.apply_message(&msg));
+        }
+        assert_eq!(shown, before);
+        assert_eq!(shown.pad_light(3)?, PadLight::Rgb(orange));
+        assert_eq!(midi_out.leds(), &before);
+        Ok(())
+    }
+}
@@ -1,46 +1,145 @@
+use crate::enums::button::led_button::LedButton;
+use crate::enums::button::pads::PAD_COUNT;
+use crate::enums::led_light::button_state::ButtonLedState;
 use crate::enums::led_light::color::LedColor;
 use crate::enums::led_light::mode::LedMode;
+use crate::enums::led_light::rgb::RgbColor;
+use crate::enums::port_selector::PortSelector;
 use crate::errors::io::{ChannelCreationError, TransmissionError};
 use crate::io::channel::Channel;
+use crate::io::frame::LedFrame;
+use crate::io::identity::{
+    APC_KEY_25_MK2_PRODUCT_ID, APC40_MK2_PRODUCT_ID, DEVICE_INQUIRY_REQUEST,
+};
+use crate::io::notification::Notification;
+use crate::io::transport::hardware::Midir;
+use crate::io::transport::{MidiBackend, MidiSink};
 use bon::bon;
 use midir::{MidiOutput, MidiOutputConnection};
+use std::ops::RangeInclusive;
+use std::thread;
 
-pub struct ChannelOutput {
-    output_connection: MidiOutputConnection,
+/// Button LEDs are driven by note on messages on the first MIDI channel
+pub const BUTTON_LED_STATUS: u8 = 0x90;
+
+pub struct ChannelOutput<O = MidiOutputConnection> {
+    output_connection: O,
+    /// Pad and button LED messages sent so far
+    leds: LedFrame,
 }
 
 #[bon]
 impl ChannelOutput {
+    /// Opens the output port given by `port` or, when missing, by `port_selector`.
+    /// By default, the first port that looks like an APC Key 25 is used
     #[builder]
     pub fn new(
         port: Option<usize>,
+        port_selector: Option<PortSelector>,
         initialize_note_led: Option<bool>,
         port_name: Option<&str>,
     ) -> Result<Self, ChannelCreationError> {
-        let midi_output = Self::get_midi()?;
-        let out_ports = midi_output.ports();
-        let chosen_port = port.unwrap_or(2);
-        let out_port = out_ports
-            .get(chosen_port)
-            .ok_or(ChannelCreationError::PortOutOfRange(chosen_port))?;
-
-        let mut output_connection = midi_output
-            .connect(out_port, port_name.unwrap_or("akai-midir-write-output"))
-            .map_err(|e| ChannelCreationError::EstablishingOutputConnection { source: e })?;
+        let selector = port.map_or_else(|| port_selector.unwrap_or_default(), PortSelector::Index);
+        let mut output_connection =
+            Midir.open_sink(&selector, port_name.unwrap_or("akai-midir-write-output"))?;
         if initialize_note_led.unwrap_or(false) {
             initialize_pads_led(&mut output_connection)
                 .map_err(|e| ChannelCreationError::LedInitialization { source: e })?;
         }
-        Ok(Self { output_connection })
+        Ok(Self::from_sink(output_connection))
+    }
+}
+
+impl<O: MidiSink> ChannelOutput<O> {
+    /// Builds an output channel on top of any transport, e.g. a `LoopbackDevice` output when there is no hardware at hand.
+    /// Unlike the builder, it does not send anything: call `initialize_notes_led` if needed
+    #[must_use]
+    pub const fn from_sink(output_connection: O) -> Self {
+        Self {
+            output_connection,
+            leds: LedFrame::new(),
+        }
+    }
+
+    /// Lights as set through this output, starting from all off, RGB colours included.
+    ///
+    /// Writes are recorded even when they fail to reach the device, so this is what the device should show
+    /// and what `resend_leds` restores after a reconnection
+    #[must_use]
+    pub const fn leds(&self) -> &LedFrame {
+        &self.leds
+    }
+
+    /// Resets the recorded lights to all off without sending anything, nothing will be restored by `resend_leds`
+    pub fn forget_leds(&mut self) {
+        self.leds.clear();
+    }
+
+    /// Sends every recorded light again, e.g. after the device was unplugged or power cycled
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn resend_leds(&mut self) -> Result<(), TransmissionError> {
+        self.leds
+            .diff(None)
+            .iter()
+            .try_for_each(|led_msg| send(&mut self.output_connection, led_msg))
+    }
+
+    #[must_use]
+    pub const fn transport(&self) -> &O {
+        &self.output_connection
+    }
+
+    pub const fn transport_mut(&mut self) -> &mut O {
+        &mut self.output_connection
+    }
+
+    #[must_use]
+    pub fn into_transport(self) -> O {
+        self.output_connection
     }
 
     /// # Errors
     ///
     /// Will return `TransmissionError` if data is not valid or there are low-level issues communicating with the device
     pub fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError> {
+        self.leds.apply_message(data);
         send(&mut self.output_connection, data)
     }
 
+    /// Plays the notification, blocking until it is over, then restores the lights it covered.
+    /// From a listener callback, prefer `ApcKey25::notify` which does not hold up the input
+    /// ```Rust
+    ///midi_out.notify(&Notification::error())?;
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn notify(&mut self, notification: &Notification) -> Result<(), TransmissionError> {
+        let mut shown = self.leds.clone();
+        for step in 0..notification.step_count() {
+            let frame = notification.render(step, &self.leds);
+            self.show_unrecorded(&frame, &mut shown)?;
+            thread::sleep(notification.step());
+        }
+        let leds = self.leds.clone();
+        self.show_unrecorded(&leds, &mut shown)
+    }
+
+    /// Sends what differs between `shown` and `frame` without recording it in `leds`, for temporary patterns
+    pub(crate) fn show_unrecorded(
+        &mut self,
+        frame: &LedFrame,
+        shown: &mut LedFrame,
+    ) -> Result<(), TransmissionError> {
+        for led_msg in frame.diff(Some(shown)) {
+            send(&mut self.output_connection, &led_msg)?;
+        }
+        shown.clone_from(frame);
+        Ok(())
+    }
+
     /// # Errors
     ///
     /// Will return `TransmissionError` if there are low-level issues communicating with the device
@@ -48,36 +147,141 @@ impl ChannelOutput {
         initialize_pads_led(&mut self.output_connection)
     }
 
+    /// Asks the device to identify itself, the reply is received by the input channel.
+    /// `InputChannel::identify` takes care of both sides
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn request_device_identity(&mut self) -> Result<(), TransmissionError> {
+        self.send(&DEVICE_INQUIRY_REQUEST)
+    }
+
     /// # Errors
     ///
     /// Will return `TransmissionError` if there are low-level issues communicating with the device
-    pub fn set_all_pads_color(
+    pub fn set_all_pads_color<M>(
         &mut self,
-        led_mode: LedMode,
+        led_mode: M,
         color: LedColor,
-    ) -> Result<(), TransmissionError> {
+    ) -> Result<(), TransmissionError>
+    where
+        M: Into<LedMode>,
+    {
+        let led_mode = led_mode.into();
         initialize_pads_led(&mut self.output_connection)?;
-        for i in 0..=86 {
+        for i in 0..PAD_COUNT {
             self.set_pad_led(led_mode, i, color)?;
         }
         Ok(())
     }
 
+    /// `led_mode` is either a `LedMode` or a `LedBehavior`
     /// # Errors
     ///
     /// Will return `TransmissionError` if there are low-level issues communicating with the device
-    pub fn set_pad_led<T>(
+    pub fn set_pad_led<M, T>(
         &mut self,
-        led_mode: LedMode,
+        led_mode: M,
         note: T,
         color: LedColor,
     ) -> Result<(), TransmissionError>
     where
+        M: Into<LedMode>,
         T: Into<u8>,
     {
-        let led_msg = [led_mode.into(), note.into(), color.into()];
+        let led_msg = [led_mode.into().into(), note.into(), color.into()];
         self.send(led_msg.as_ref())
     }
+
+    /// Sets any colour on a pad with the mk2 RGB system exclusive message, beyond the 128 entries of the `LedColor` palette.
+    ///
+    /// The pad stays solid, use `set_pad_led` afterwards for the pulse and blink modes
+    /// ```Rust
+    ///midi_out.set_pad_rgb(PadKey::Grid(1, 1), RgbColor::new(255, 128, 0))?;
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_pad_rgb<T>(&mut self, note: T, color: RgbColor) -> Result<(), TransmissionError>
+    where
+        T: Into<u8>,
+    {
+        let note = note.into();
+        self.set_pads_rgb(&[(note..=note, color)])
+    }
+
+    /// Sets every pad from `start` to `end`, both included, to the same colour in a single message
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_pad_range_rgb<T>(
+        &mut self,
+        start: T,
+        end: T,
+        color: RgbColor,
+    ) -> Result<(), TransmissionError>
+    where
+        T: Into<u8>,
+    {
+        self.set_pads_rgb(&[(start.into()..=end.into(), color)])
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_all_pads_rgb(&mut self, color: RgbColor) -> Result<(), TransmissionError> {
+        self.set_pads_rgb(&[(0..=PAD_COUNT - 1, color)])
+    }
+
+    /// Sets several pad ranges, each to its own colour, in a single system exclusive message
+    /// ```Rust
+    ///midi_out.set_pads_rgb(&[
+    ///    (0..=7, RgbColor::new(255, 0, 0)),
+    ///    (8..=15, RgbColor::from_hex("#00FF80").unwrap()),
+    ///])?;
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_pads_rgb(
+        &mut self,
+        ranges: &[(RangeInclusive<u8>, RgbColor)],
+    ) -> Result<(), TransmissionError> {
+        if ranges.is_empty() {
+            return Ok(());
+        }
+        self.send(&rgb_sysex(ranges))
+    }
+
+    /// Sets the single colour LED of an arrow, knob control or soft key button
+    /// ```Rust
+    ///midi_out.set_button_led(SoftKey::RecArm, ButtonLedState::Blinking)?;
+    ///midi_out.set_button_led(KnobCtrlKey::Volume, ButtonLedState::On)?;
+    ///midi_out.set_button_led(LedButton::Up, ButtonLedState::Off)?;
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_button_led<T>(
+        &mut self,
+        button: T,
+        state: ButtonLedState,
+    ) -> Result<(), TransmissionError>
+    where
+        T: Into<LedButton>,
+    {
+        let led_msg = [BUTTON_LED_STATUS, button.into().into(), state.into()];
+        self.send(led_msg.as_ref())
+    }
+
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn set_all_button_leds(&mut self, state: ButtonLedState) -> Result<(), TransmissionError> {
+        LedButton::ALL
+            .iter()
+            .try_for_each(|button| self.set_button_led(*button, state))
+    }
 }
 
 impl Channel for ChannelOutput {
@@ -88,34 +292,189 @@ impl Channel for ChannelOutput {
     }
 }
 
-fn initialize_pads_led(
-    output_connection: &mut MidiOutputConnection,
-) -> Result<(), TransmissionError> {
-    let init_sysex: Vec<u8> = vec![
+/// Introduction message that initializes the pad LEDs, sent by `initialize_notes_led`
+#[rustfmt::skip]
+pub(crate) const INIT_SYSEX: [u8; 12] = [
+    0xF0, // SysEx start
+    0x47, // Akai manufacturer ID
+    0x7F, // Device ID (all devices)
+    APC40_MK2_PRODUCT_ID as u8,
+    0x60, // Message type: Initialize
+    0x00, 0x04, // Application version (can be any value)
+    0x42, // Content version
+    0x08, 0x02, 0x01, // Mode/configuration
+    0xF7, // SysEx end
+];
+
+fn initialize_pads_led(output_connection: &mut impl MidiSink) -> Result<(), TransmissionError> {
+    send(output_connection, &INIT_SYSEX)
+}
+
+pub(crate) fn rgb_sysex(ranges: &[(RangeInclusive<u8>, RgbColor)]) -> Vec<u8> {
+    let payload: Vec<u8> = ranges
+        .iter()
+        .flat_map(|(pads, color)| {
+            let (start, end) = (*pads.start().min(pads.end()), *pads.start().max(pads.end()));
+            [start & 0x7F, end & 0x7F]
+                .into_iter()
+                .chain(color.to_sysex_bytes())
+        })
+        .collect();
+    let length = u16::try_from(payload.len()).unwrap_or(u16::MAX);
+    let mut rgb_sysex = rgb_sysex_header(length).to_vec();
+    rgb_sysex.extend(payload);
+    rgb_sysex.push(0xF7); // SysEx end
+    rgb_sysex
+}
+
+/// Pad ranges and colours of a message built by `rgb_sysex`, `None` for any other message
+pub(crate) fn parse_rgb_sysex(data: &[u8]) -> Option<Vec<(RangeInclusive<u8>, RgbColor)>> {
+    let payload = data.get(RGB_SYSEX_HEADER_LEN..data.len().checked_sub(1)?)?;
+    let length = u16::try_from(payload.len()).ok()?;
+    if data[..RGB_SYSEX_HEADER_LEN] != rgb_sysex_header(length)
+        || data.last() != Some(&0xF7)
+        || payload.len() % 8 != 0
+    {
+        return None;
+    }
+    payload
+        .chunks_exact(8)
+        .map(|range| {
+            let color: [u8; 6] = range[2..].try_into().ok()?;
+            Some((range[0]..=range[1], RgbColor::from_sysex_bytes(color)))
+        })
+        .collect()
+}
+
+const RGB_SYSEX_HEADER_LEN: usize = 7;
+
+const fn rgb_sysex_header(length: u16) -> [u8; RGB_SYSEX_HEADER_LEN] {
+    [
         0xF0, // SysEx start
         0x47, // Akai manufacturer ID
         0x7F, // Device ID (all devices)
-        0x29, // Product ID for APC Key 25 mk2
-        0x60, // Message type: Initialize
-        0x00, 0x04, // Application version (can be any value)
-        0x42, // Content version
-        0x08, 0x02, 0x01, // Mode/configuration
-        0xF7, // SysEx end
-    ];
-    send(output_connection, &init_sysex)
+        APC_KEY_25_MK2_PRODUCT_ID as u8,
+        0x24,                       // Message type: Set RGB LED colour
+        (length >> 7) as u8 & 0x7F, // Payload length MSB
+        length as u8 & 0x7F,        // Payload length LSB
+    ]
 }
 
 /// # Errors
 ///
 /// Will return `TransmissionError` if it's not a valid MIDI message or there are low-level issue communicating with the device
-fn send(
-    output_connection: &mut MidiOutputConnection,
-    data: &[u8],
-) -> Result<(), TransmissionError> {
-    output_connection
-        .send(data)
-        .map_err(|e| TransmissionError::Send {
-            data: data.to_vec(),
-            source: e,
-        })
+fn send(output_connection: &mut impl MidiSink, data: &[u8]) -> Result<(), TransmissionError> {
+    output_connection.send(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelOutput, parse_rgb_sysex, rgb_sysex};
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::errors::io::TransmissionError;
+    use crate::io::transport::MidiSink;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::ops::RangeInclusive;
+
+    /// Fails every write, like `SupervisedOutput` while the device is unplugged
+    struct Unplugged;
+
+    impl MidiSink for Unplugged {
+        fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError> {
+            Err(TransmissionError::Disconnected {
+                data: data.to_vec(),
+            })
+        }
+    }
+
+    #[test]
+    fn failed_writes_are_recorded_and_resent() -> Result<(), Box<dyn Error>> {
+        let mut unplugged = ChannelOutput::from_sink(Unplugged);
+        assert!(
+            unplugged
+                .set_pad_led(LedMode::Pulsing1over8, 3, LedColor::Red)
+                .is_err()
+        );
+        assert!(
+            unplugged
+                .set_button_led(SoftKey::Mute, ButtonLedState::On)
+                .is_err()
+        );
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        midi_out.leds.clone_from(unplugged.leds());
+        midi_out.resend_leds()?;
+        let received = device.received();
+        assert_eq!(received.len(), 40 + 13);
+        assert!(received.contains(&vec![0x98, 0x03, 0x05]));
+        assert!(received.contains(&vec![0x90, u8::from(SoftKey::Mute), 0x01]));
+        Ok(())
+    }
+
+    #[test]
+    fn forgotten_leds_are_resent_off() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        midi_out.set_pad_led(LedMode::On100Percent, 3, LedColor::Red)?;
+        midi_out.forget_leds();
+        let _ = device.take_received();
+        midi_out.resend_leds()?;
+        assert!(device.received().contains(&vec![0x96, 0x03, 0x00]));
+        Ok(())
+    }
+
+    #[test]
+    fn rgb_sysex_splits_each_channel_in_two_bytes() {
+        assert_eq!(
+            rgb_sysex(&[(3..=3, RgbColor::new(255, 128, 0))]),
+            vec![
+                0xF0, 0x47, 0x7F, 0x4E, 0x24, 0x00, 0x08, 0x03, 0x03, 0x01, 0x7F, 0x01, 0x00, 0x00,
+                0x00, 0xF7,
+            ]
+        );
+    }
+
+    #[test]
+    fn rgb_sysex_orders_reversed_ranges() {
+        let sysex = rgb_sysex(&[
+            (RangeInclusive::new(7, 0), RgbColor::WHITE),
+            (8..=15, RgbColor::new(0, 0, 1)),
+        ]);
+        assert_eq!(&sysex[5..7], &[0x00, 0x10]);
+        assert_eq!(&sysex[7..9], &[0x00, 0x07]);
+        assert_eq!(&sysex[15..23], &[0x08, 0x0F, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(sysex.last(), Some(&0xF7));
+    }
+
+    #[test]
+    fn set_pads_rgb_sends_one_message_and_nothing_for_no_range() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        midi_out.set_pads_rgb(&[])?;
+        assert!(device.received().is_empty());
+        midi_out.set_all_pads_rgb(RgbColor::BLACK)?;
+        assert_eq!(
+            device.received(),
+            vec![rgb_sysex(&[(0..=39, RgbColor::BLACK)])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rgb_sysex_parses_back_to_its_ranges() {
+        let ranges = vec![
+            (0..=7, RgbColor::new(255, 128, 0)),
+            (39..=39, RgbColor::new(1, 2, 200)),
+        ];
+        assert_eq!(parse_rgb_sysex(&rgb_sysex(&ranges)), Some(ranges));
+        assert_eq!(parse_rgb_sysex(&[0x96, 0x03, 0x05]), None);
+        let mut truncated = rgb_sysex(&[(0..=0, RgbColor::WHITE)]);
+        truncated.remove(8);
+        assert_eq!(parse_rgb_sysex(&truncated), None);
+    }
 }
@@ -0,0 +1,108 @@
+use crate::enums::overflow_policy::OverflowPolicy;
+use crate::io::sync::lock;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+struct State<I> {
+    events: VecDeque<I>,
+    dropped: u64,
+    waker: Option<Waker>,
+}
+
+/// Bounded buffer between the MIDI thread, that pushes decoded events, and the consumer
+pub struct EventQueue<I> {
+    capacity: usize,
+    overflow: OverflowPolicy,
+    state: Mutex<State<I>>,
+    available: Condvar,
+}
+
+impl<I> EventQueue<I> {
+    pub fn new(capacity: usize, overflow: OverflowPolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            overflow,
+            state: Mutex::new(State {
+                events: VecDeque::with_capacity(capacity),
+                dropped: 0,
+                waker: None,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    pub fn push(&self, event: I) {
+        let mut state = lock(&self.state);
+        if state.events.len() >= self.capacity {
+            state.dropped += 1;
+            match self.overflow {
+                OverflowPolicy::DropOldest => {
+                    state.events.pop_front();
+                }
+                OverflowPolicy::DropNewest => return,
+            }
+        }
+        state.events.push_back(event);
+        let waker = state.waker.take();
+        drop(state);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        self.available.notify_one();
+    }
+
+    pub fn try_pop(&self) -> Option<I> {
+        lock(&self.state).events.pop_front()
+    }
+
+    /// Blocks until an event is available
+    pub fn pop(&self) -> I {
+        let mut state = lock(&self.state);
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                return event;
+            }
+            state = self
+                .available
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Blocks until an event is available or the timeout expires, a timeout too long to be represented never expires
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<I> {
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return Some(self.pop());
+        };
+        let mut state = lock(&self.state);
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                return Some(event);
+            }
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            state = self
+                .available
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Pops the oldest event, registering the waker to be notified of the next push when there is none
+    #[cfg(feature = "async")]
+    pub fn pop_or_register(&self, waker: &Waker) -> Option<I> {
+        let mut state = lock(&self.state);
+        let event = state.events.pop_front();
+        if event.is_none() {
+            state.waker = Some(waker.clone());
+        }
+        event
+    }
+
+    pub fn dropped(&self) -> u64 {
+        lock(&self.state).dropped
+    }
+}
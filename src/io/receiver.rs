@@ -0,0 +1,202 @@
+use crate::enums::input_group::ChannelKind;
+use crate::enums::overflow_policy::OverflowPolicy;
+use crate::errors::io::ChannelCreationError;
+use crate::io::input::InputChannel;
+use crate::io::input_data::MidiInputData;
+use crate::io::queue::EventQueue;
+use crate::io::transport::MidiSource;
+use crate::io::transport::hardware::MidirInput;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Decoded input events for a plain main loop, fed by the MIDI thread through a bounded buffer.
+///
+/// Iterating over it blocks waiting for the next event and never ends, dropping it closes the connection
+pub struct InputReceiver<G, S: MidiSource = MidirInput> {
+    queue: Arc<EventQueue<MidiInputData<G>>>,
+    _connection: S::Connection<()>,
+}
+
+impl<G, S: MidiSource> InputReceiver<G, S> {
+    /// Blocks until the next event arrives
+    #[must_use]
+    pub fn recv(&self) -> MidiInputData<G> {
+        self.queue.pop()
+    }
+
+    /// Returns the next event if there is one already waiting
+    #[must_use]
+    pub fn try_recv(&self) -> Option<MidiInputData<G>> {
+        self.queue.try_pop()
+    }
+
+    /// Blocks until the next event arrives, giving up after `timeout`
+    #[must_use]
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<MidiInputData<G>> {
+        self.queue.pop_timeout(timeout)
+    }
+
+    /// Events already waiting, without blocking
+    pub fn try_iter(&self) -> impl Iterator<Item = MidiInputData<G>> + '_ {
+        std::iter::from_fn(|| self.try_recv())
+    }
+
+    /// Number of events discarded so far because the buffer was full
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped()
+    }
+}
+
+impl<G, S: MidiSource> Iterator for InputReceiver<G, S> {
+    type Item = MidiInputData<G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.recv())
+    }
+}
+
+impl<S: MidiSource> InputChannel<S> {
+    /// Turns the input channel into a blocking receiver of decoded events.
+    /// At most `capacity` events are buffered, `overflow` decides which one is lost when the main loop lags behind
+    /// ```Rust
+    ///let midi_in = ramidier::io::input::InputChannel::builder().build()?;
+    ///let events = midi_in.into_receiver(PadsAndKnobsChannel, 64, OverflowPolicy::DropOldest)?;
+    ///for event in events {
+    ///    println!("{event:?}");
+    ///}
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `ChannelCreationError` if there are low-level issues communicating with the device
+    pub fn into_receiver<C>(
+        self,
+        channel_type: C,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> Result<InputReceiver<C::Group, S>, ChannelCreationError>
+    where
+        C: ChannelKind + Send + 'static,
+        C::Group: Send + 'static,
+    {
+        let queue = Arc::new(EventQueue::new(capacity, overflow));
+        let producer = Arc::clone(&queue);
+        let connection = self.listen(
+            Some("akai-midir-receiver-input"),
+            move |_, input, ()| producer.push(input),
+            (),
+            channel_type,
+        )?;
+        Ok(InputReceiver {
+            queue,
+            _connection: connection,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::enums::overflow_policy::OverflowPolicy;
+    use crate::io::input::InputChannel;
+    use crate::io::input_data::InputAction;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn empty_receiver_does_not_block_on_try_recv() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let events = InputChannel::from_source(device.input()).into_receiver(
+            PadsAndKnobsChannel,
+            8,
+            OverflowPolicy::default(),
+        )?;
+        assert!(events.try_recv().is_none());
+        device.send(&[0xB0, 0x30, 0x01]);
+        device.send(&[0xB0, 0x30, 0x02]);
+        assert_eq!(
+            events
+                .try_iter()
+                .map(|event| event.action)
+                .collect::<Vec<_>>(),
+            vec![
+                InputAction::Changed { value: 1 },
+                InputAction::Changed { value: 2 }
+            ]
+        );
+        assert!(events.try_recv().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn recv_timeout_expires_without_events() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let events = InputChannel::from_source(device.input()).into_receiver(
+            PadsAndKnobsChannel,
+            8,
+            OverflowPolicy::default(),
+        )?;
+        let start = Instant::now();
+        assert!(events.recv_timeout(Duration::from_millis(20)).is_none());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        Ok(())
+    }
+
+    #[test]
+    fn recv_timeout_accepts_the_longest_duration() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let events = InputChannel::from_source(device.input()).into_receiver(
+            PadsAndKnobsChannel,
+            8,
+            OverflowPolicy::default(),
+        )?;
+        device.send(&[0x90, 0x62, 0x7F]);
+        assert!(
+            events
+                .recv_timeout(Duration::MAX)
+                .is_some_and(|event| event.input_group == PadsAndKnobsInputGroup::Shift)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn recv_timeout_returns_an_event_sent_while_waiting() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut events = InputChannel::from_source(device.input()).into_receiver(
+            PadsAndKnobsChannel,
+            8,
+            OverflowPolicy::default(),
+        )?;
+        let sender = device.clone();
+        let worker = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            sender.send(&[0x90, 0x62, 0x7F]);
+        });
+        let event = events.recv_timeout(Duration::from_secs(5));
+        worker.join().map_err(|_| "sender panicked")?;
+        assert!(matches!(
+            event,
+            Some(event) if event.input_group == PadsAndKnobsInputGroup::Shift && event.is_pressed()
+        ));
+        device.send(&[0x80, 0x62, 0x00]);
+        assert!(events.next().is_some_and(|event| event.is_released()));
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_the_receiver_closes_the_connection() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let events = InputChannel::from_source(device.input()).into_receiver(
+            PadsAndKnobsChannel,
+            8,
+            OverflowPolicy::default(),
+        )?;
+        assert_eq!(device.listener_count(), 1);
+        drop(events);
+        assert_eq!(device.listener_count(), 0);
+        device.send(&[0x90, 0x27, 0x7F]);
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use crate::enums::input_group::{CombinedInputGroup, PadsAndKnobsInputGroup};
+use crate::io::input_data::{InputAction, MidiInputData};
+use bon::bon;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LayeredInputGroup {
+    /// Used without Shift, including the Shift button itself unless suppressed
+    Base(PadsAndKnobsInputGroup),
+    /// Used while Shift was held
+    Shifted(PadsAndKnobsInputGroup),
+}
+
+impl LayeredInputGroup {
+    #[must_use]
+    pub const fn input_group(self) -> PadsAndKnobsInputGroup {
+        match self {
+            Self::Base(input_group) | Self::Shifted(input_group) => input_group,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_shifted(self) -> bool {
+        matches!(self, Self::Shifted(_))
+    }
+}
+
+/// The button itself, whichever layer it was used in
+impl From<LayeredInputGroup> for CombinedInputGroup {
+    fn from(value: LayeredInputGroup) -> Self {
+        Self::PadsAndKnobs(value.input_group())
+    }
+}
+
+/// Modifier aware decoding for the dual function buttons, fed with the decoded input of any listener.
+///
+/// A release always belongs to the same layer as its press, even if Shift was released in between
+/// ```Rust
+///let mut shift_layer = ShiftLayer::builder().suppress_shift(true).build();
+///let _conn_in = midi_in.listen(
+///    None,
+///    move |_, rx_data, ()| match shift_layer.process(rx_data).map(|i| i.input_group) {
+///        Some(LayeredInputGroup::Shifted(PadsAndKnobsInputGroup::SoftKeys(k))) => println!("Shift + {k:?}"),
+///        Some(input_group) => println!("{input_group:?}"),
+///        None => (),
+///    },
+///    (),
+///    PadsAndKnobsChannel,
+/// )?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ShiftLayer {
+    suppress_shift: bool,
+    shift_held: bool,
+    /// Bit set of the notes pressed while Shift was held
+    shifted_presses: u128,
+}
+
+#[bon]
+impl ShiftLayer {
+    /// When `suppress_shift` is true, Shift presses and releases only update the layer and are not emitted
+    #[builder]
+    pub fn new(suppress_shift: Option<bool>) -> Self {
+        Self {
+            suppress_shift: suppress_shift.unwrap_or(false),
+            ..Self::default()
+        }
+    }
+}
+
+impl ShiftLayer {
+    #[must_use]
+    pub const fn is_shift_held(&self) -> bool {
+        self.shift_held
+    }
+
+    pub fn process(
+        &mut self,
+        input: MidiInputData<PadsAndKnobsInputGroup>,
+    ) -> Option<MidiInputData<LayeredInputGroup>> {
+        let note = u8::from(input.input_group) & 0x7F;
+        let note_bit = 1u128 << note;
+        let shifted = match (input.input_group, input.action) {
+            (PadsAndKnobsInputGroup::Shift, action) => {
+                self.shift_held = matches!(action, InputAction::Pressed { .. });
+                if self.suppress_shift {
+                    return None;
+                }
+                false
+            }
+            (_, InputAction::Pressed { .. }) => {
+                if self.shift_held {
+                    self.shifted_presses |= note_bit;
+                }
+                self.shift_held
+            }
+            (_, InputAction::Released { .. }) => {
+                let shifted = self.shifted_presses & note_bit != 0;
+                self.shifted_presses &= !note_bit;
+                shifted
+            }
+            (_, InputAction::Changed { .. }) => self.shift_held,
+        };
+        Some(MidiInputData {
+            channel: input.channel,
+            input_group: if shifted {
+                LayeredInputGroup::Shifted(input.input_group)
+            } else {
+                LayeredInputGroup::Base(input.input_group)
+            },
+            action: input.action,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LayeredInputGroup, ShiftLayer};
+    use crate::enums::button::pads::PadKey;
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::io::input_data::InputAction;
+    use crate::io::transport::loopback::listen_to;
+    use std::error::Error;
+
+    const PRESSED: InputAction = InputAction::Pressed { velocity: 0x7F };
+    const RELEASED: InputAction = InputAction::Released { velocity: 0 };
+
+    /// Feeds the messages through a listener and returns what the shift layer emitted
+    fn layered(
+        shift_layer: ShiftLayer,
+        messages: &[[u8; 3]],
+    ) -> Result<Vec<(LayeredInputGroup, InputAction)>, Box<dyn Error>> {
+        let (_, layered) = listen_to(
+            PadsAndKnobsChannel,
+            messages,
+            (shift_layer, Vec::new()),
+            |input, (shift_layer, layered): &mut (ShiftLayer, Vec<_>)| {
+                if let Some(input) = shift_layer.process(input) {
+                    layered.push((input.input_group, input.action));
+                }
+            },
+        )?;
+        Ok(layered)
+    }
+
+    #[test]
+    fn release_stays_in_the_layer_of_its_press() -> Result<(), Box<dyn Error>> {
+        let pad = PadsAndKnobsInputGroup::Pads(PadKey::Grid(5, 1));
+        let received = layered(
+            ShiftLayer::builder().suppress_shift(true).build(),
+            &[
+                [0x90, 0x62, 0x7F],
+                [0x90, 0x00, 0x7F],
+                [0x80, 0x62, 0x00],
+                [0x80, 0x00, 0x00],
+                [0x90, 0x00, 0x7F],
+            ],
+        )?;
+        assert_eq!(
+            received,
+            vec![
+                (LayeredInputGroup::Shifted(pad), PRESSED),
+                (LayeredInputGroup::Shifted(pad), RELEASED),
+                (LayeredInputGroup::Base(pad), PRESSED),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shift_itself_is_emitted_unless_suppressed() -> Result<(), Box<dyn Error>> {
+        let mute = PadsAndKnobsInputGroup::SoftKeys(SoftKey::Mute);
+        let received = layered(
+            ShiftLayer::default(),
+            &[[0x90, 0x62, 0x7F], [0x90, 0x54, 0x7F], [0x80, 0x62, 0x00]],
+        )?;
+        assert_eq!(
+            received,
+            vec![
+                (
+                    LayeredInputGroup::Base(PadsAndKnobsInputGroup::Shift),
+                    PRESSED
+                ),
+                (LayeredInputGroup::Shifted(mute), PRESSED),
+                (
+                    LayeredInputGroup::Base(PadsAndKnobsInputGroup::Shift),
+                    RELEASED
+                ),
+            ]
+        );
+        Ok(())
+    }
+}
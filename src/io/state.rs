@@ -0,0 +1,331 @@
+use crate::enums::button::led_button::LedButton;
+use crate::enums::button::pads::PadKey;
+use crate::enums::input_group::{CombinedInputGroup, KeyboardInputGroup, PadsAndKnobsInputGroup};
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::errors::mapping::HardwareMappingError;
+use crate::io::frame::{PadLed, PadLight};
+use crate::io::input_data::{InputAction, MidiInputData};
+use crate::io::output::ChannelOutput;
+use crate::io::sync::lock;
+use crate::io::transport::MidiSink;
+use crate::knob::encoder::{KNOB_COUNT, knob_slot, relative_delta};
+use midir::MidiOutputConnection;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Button, pad or key currently pressed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HeldInput {
+    pub input_group: CombinedInputGroup,
+    pub velocity: u8,
+    /// When the press was processed
+    pub since: Instant,
+}
+
+impl HeldInput {
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.since.elapsed()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+struct KnobState {
+    last_value: Option<u8>,
+    position: i32,
+}
+
+#[derive(Debug, Default)]
+struct InputState {
+    /// In the order they were pressed
+    held: Vec<HeldInput>,
+    knobs: [KnobState; KNOB_COUNT as usize],
+}
+
+impl InputState {
+    fn process(&mut self, input_group: CombinedInputGroup, action: InputAction) {
+        match action {
+            InputAction::Pressed { velocity } => {
+                self.held.retain(|held| held.input_group != input_group);
+                self.held.push(HeldInput {
+                    input_group,
+                    velocity,
+                    since: Instant::now(),
+                });
+            }
+            InputAction::Released { .. } => {
+                self.held.retain(|held| held.input_group != input_group);
+            }
+            InputAction::Changed { value } => {
+                if let CombinedInputGroup::PadsAndKnobs(PadsAndKnobsInputGroup::Knob(knob)) =
+                    input_group
+                {
+                    if let Ok(slot) = knob_slot(knob) {
+                        let knob = &mut self.knobs[slot];
+                        knob.last_value = Some(value);
+                        knob.position += i32::from(relative_delta(value));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// What the controller looks like right now: the inputs come from the decoded input of any listener,
+/// the lights are read from the LEDs recorded by the output.
+///
+/// Clones share the same state, keep one to query and move another into the listener.
+/// Presses are timed when they are processed, so feed the tracker from the listener callback rather than later on.
+/// The light queries lock the output: inside an `ApcKey25` callback, read `ChannelOutput::leds` from the given output instead
+/// ```Rust
+///let mut device = ApcKey25::builder().build()?;
+///let state = ControllerState::new(device.shared_output());
+///let tracker = state.clone();
+///device.listen(move |_, rx_data, _, ()| tracker.process(rx_data), (), CombinedChannel)?;
+///device.set_pad_led(LedMode::On100Percent, PadKey::Grid(3, 4), LedColor::Red)?;
+///println!("held pads: {:?}", state.held_pads());
+///println!("pad (3, 4) is {:?}", state.pad_light(PadKey::Grid(3, 4))?);
+/// ```
+pub struct ControllerState<O = MidiOutputConnection> {
+    inputs: Arc<Mutex<InputState>>,
+    output: Arc<Mutex<ChannelOutput<O>>>,
+}
+
+impl<O> Clone for ControllerState<O> {
+    fn clone(&self) -> Self {
+        Self {
+            inputs: Arc::clone(&self.inputs),
+            output: Arc::clone(&self.output),
+        }
+    }
+}
+
+impl<O: MidiSink> ControllerState<O> {
+    /// Tracks the lights of the output, e.g. `ApcKey25::shared_output` or `DeviceSupervisor::shared_output`
+    #[must_use]
+    pub fn new(output: Arc<Mutex<ChannelOutput<O>>>) -> Self {
+        Self {
+            inputs: Arc::default(),
+            output,
+        }
+    }
+
+    /// Updates the held inputs and the knobs. Accepts the input of any channel kind and the output of `ShiftLayer`,
+    /// whose suppressed Shift presses are not seen
+    pub fn process<G>(&self, input: MidiInputData<G>)
+    where
+        G: Into<CombinedInputGroup>,
+    {
+        lock(&self.inputs).process(input.input_group.into(), input.action);
+    }
+
+    /// Forgets the held inputs, for instance after a reconnection where releases may have been lost
+    pub fn release_all(&self) {
+        lock(&self.inputs).held.clear();
+    }
+
+    /// Held inputs in the order they were pressed
+    #[must_use]
+    pub fn held(&self) -> Vec<HeldInput> {
+        lock(&self.inputs).held.clone()
+    }
+
+    #[must_use]
+    pub fn held_pads(&self) -> Vec<PadKey> {
+        lock(&self.inputs)
+            .held
+            .iter()
+            .filter_map(|held| match held.input_group {
+                CombinedInputGroup::PadsAndKnobs(PadsAndKnobsInputGroup::Pads(pad)) => Some(pad),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Keyboard keys from 1 to 25
+    #[must_use]
+    pub fn held_keys(&self) -> Vec<u8> {
+        lock(&self.inputs)
+            .held
+            .iter()
+            .filter_map(|held| match held.input_group {
+                CombinedInputGroup::Keyboard(KeyboardInputGroup::Key(key)) => Some(key),
+                CombinedInputGroup::PadsAndKnobs(_) => None,
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn held_input<G>(&self, input_group: G) -> Option<HeldInput>
+    where
+        G: Into<CombinedInputGroup>,
+    {
+        let input_group = input_group.into();
+        lock(&self.inputs)
+            .held
+            .iter()
+            .find(|held| held.input_group == input_group)
+            .copied()
+    }
+
+    #[must_use]
+    pub fn is_held<G>(&self, input_group: G) -> bool
+    where
+        G: Into<CombinedInputGroup>,
+    {
+        self.held_input(input_group).is_some()
+    }
+
+    /// How long the input has been held, `None` if it is not
+    #[must_use]
+    pub fn hold_duration<G>(&self, input_group: G) -> Option<Duration>
+    where
+        G: Into<CombinedInputGroup>,
+    {
+        self.held_input(input_group).map(|held| held.duration())
+    }
+
+    /// Last raw value sent by the knob, `None` until it is turned
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn knob_value(&self, knob: u8) -> Result<Option<u8>, HardwareMappingError> {
+        knob_slot(knob).map(|slot| lock(&self.inputs).knobs[slot].last_value)
+    }
+
+    /// Sum of the relative increments sent by the knob since the tracker was created
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn knob_position(&self, knob: u8) -> Result<i32, HardwareMappingError> {
+        knob_slot(knob).map(|slot| lock(&self.inputs).knobs[slot].position)
+    }
+
+    /// Colour and mode of the pad, RGB colours give the nearest palette colour
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn pad_led<T>(&self, note: T) -> Result<PadLed, HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        lock(&self.output).leds().pad(note)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the note is not a pad
+    pub fn pad_light<T>(&self, note: T) -> Result<PadLight, HardwareMappingError>
+    where
+        T: Into<u8>,
+    {
+        lock(&self.output).leds().pad_light(note)
+    }
+
+    #[must_use]
+    pub fn button_led<T>(&self, button: T) -> ButtonLedState
+    where
+        T: Into<LedButton>,
+    {
+        lock(&self.output).leds().button(button)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ControllerState;
+    use crate::enums::button::pads::PadKey;
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::input_group::{CombinedChannel, PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::enums::led_light::rgb::RgbColor;
+    use crate::io::device::ApcKey25;
+    use crate::io::frame::{PadLed, PadLight};
+    use crate::io::shift_layer::ShiftLayer;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+
+    #[test]
+    fn inputs_are_tracked_from_the_listener() -> Result<(), Box<dyn Error>> {
+        let loopback = LoopbackDevice::new();
+        let mut device = ApcKey25::from_transport(loopback.input(), loopback.output(), false)?;
+        let state = ControllerState::new(device.shared_output());
+        let tracker = state.clone();
+        device.listen(
+            move |_, input, _, ()| tracker.process(input),
+            (),
+            CombinedChannel,
+        )?;
+        loopback.send(&[0x90, 0x1B, 0x7F]);
+        loopback.send(&[0x90, 0x62, 0x7F]);
+        loopback.send(&[0x91, 0x34, 0x50]);
+        loopback.send(&[0xB0, 0x31, 0x03]);
+        loopback.send(&[0xB0, 0x31, 0x7F]);
+        assert_eq!(state.held_pads(), vec![PadKey::Grid(2, 4)]);
+        assert_eq!(state.held_keys(), vec![5]);
+        assert!(state.is_held(PadsAndKnobsInputGroup::Shift));
+        assert!(state.hold_duration(PadsAndKnobsInputGroup::Shift).is_some());
+        assert_eq!(state.held().len(), 3);
+        assert_eq!(state.knob_value(2)?, Some(0x7F));
+        assert_eq!(state.knob_position(2)?, 2);
+        assert_eq!(state.knob_value(1)?, None);
+        assert!(state.knob_value(9).is_err());
+        loopback.send(&[0x80, 0x62, 0x00]);
+        assert!(!state.is_held(PadsAndKnobsInputGroup::Shift));
+        assert_eq!(state.hold_duration(PadsAndKnobsInputGroup::Shift), None);
+        state.release_all();
+        assert!(state.held().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn lights_follow_the_output_without_syncing() -> Result<(), Box<dyn Error>> {
+        let loopback = LoopbackDevice::new();
+        let device = ApcKey25::from_transport(loopback.input(), loopback.output(), false)?;
+        let state = ControllerState::new(device.shared_output());
+        device.set_pad_led(LedMode::Blinking1over4, PadKey::Grid(3, 4), LedColor::Red)?;
+        device.set_button_led(SoftKey::Solo, ButtonLedState::Blinking)?;
+        device.set_pad_rgb(PadKey::Grid(1, 1), RgbColor::new(0, 0, 255))?;
+        assert_eq!(
+            state.pad_led(PadKey::Grid(3, 4))?,
+            PadLed {
+                mode: LedMode::Blinking1over4,
+                color: LedColor::Red
+            }
+        );
+        assert_eq!(state.button_led(SoftKey::Solo), ButtonLedState::Blinking);
+        assert_eq!(
+            state.pad_light(PadKey::Grid(1, 1))?,
+            PadLight::Rgb(RgbColor::new(0, 0, 255))
+        );
+        assert!(state.pad_led(40).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn shifted_inputs_are_tracked_as_their_button() -> Result<(), Box<dyn Error>> {
+        let loopback = LoopbackDevice::new();
+        let mut device = ApcKey25::from_transport(loopback.input(), loopback.output(), false)?;
+        let state = ControllerState::new(device.shared_output());
+        let tracker = state.clone();
+        device.listen(
+            move |_, input, _, shift_layer: &mut ShiftLayer| {
+                if let Some(input) = shift_layer.process(input) {
+                    tracker.process(input);
+                }
+            },
+            ShiftLayer::default(),
+            PadsAndKnobsChannel,
+        )?;
+        loopback.send(&[0x90, 0x62, 0x7F]);
+        loopback.send(&[0x90, 0x00, 0x7F]);
+        assert_eq!(state.held_pads(), vec![PadKey::Grid(5, 1)]);
+        assert!(state.is_held(PadsAndKnobsInputGroup::Shift));
+        loopback.send(&[0x80, 0x62, 0x00]);
+        loopback.send(&[0x80, 0x00, 0x00]);
+        assert!(state.held().is_empty());
+        Ok(())
+    }
+}
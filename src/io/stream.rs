@@ -0,0 +1,170 @@
+use crate::enums::input_group::ChannelKind;
+use crate::enums::overflow_policy::OverflowPolicy;
+use crate::errors::io::ChannelCreationError;
+use crate::io::input::InputChannel;
+use crate::io::input_data::MidiInputData;
+use crate::io::queue::EventQueue;
+use crate::io::transport::MidiSource;
+use crate::io::transport::hardware::MidirInput;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Decoded input events as a `Stream`, fed by the MIDI thread through a bounded buffer.
+///
+/// The stream never ends on its own, dropping it closes the connection
+pub struct InputStream<G, S: MidiSource = MidirInput> {
+    queue: Arc<EventQueue<MidiInputData<G>>>,
+    _connection: S::Connection<()>,
+}
+
+impl<G, S: MidiSource> InputStream<G, S> {
+    /// Number of events discarded so far because the buffer was full
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped()
+    }
+}
+
+impl<G, S: MidiSource> Unpin for InputStream<G, S> {}
+
+impl<G, S: MidiSource> Stream for InputStream<G, S> {
+    type Item = MidiInputData<G>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.queue
+            .pop_or_register(cx.waker())
+            .map_or(Poll::Pending, |event| Poll::Ready(Some(event)))
+    }
+}
+
+impl<S: MidiSource> InputChannel<S> {
+    /// Turns the input channel into a `Stream` of decoded events.
+    /// At most `capacity` events are buffered, `overflow` decides which one is lost when the consumer lags behind
+    /// ```Rust
+    ///let midi_in = ramidier::io::input::InputChannel::builder().build()?;
+    ///let mut events = midi_in.into_stream(PadsAndKnobsChannel, 64, OverflowPolicy::DropOldest)?;
+    ///while let Some(event) = events.next().await {
+    ///    println!("{event:?}");
+    ///}
+    /// ```
+    /// # Errors
+    ///
+    /// Will return `ChannelCreationError` if there are low-level issues communicating with the device
+    pub fn into_stream<C>(
+        self,
+        channel_type: C,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> Result<InputStream<C::Group, S>, ChannelCreationError>
+    where
+        C: ChannelKind + Send + 'static,
+        C::Group: Send + 'static,
+    {
+        let queue = Arc::new(EventQueue::new(capacity, overflow));
+        let producer = Arc::clone(&queue);
+        let connection = self.listen(
+            Some("akai-midir-stream-input"),
+            move |_, input, ()| producer.push(input),
+            (),
+            channel_type,
+        )?;
+        Ok(InputStream {
+            queue,
+            _connection: connection,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::button::pads::PadKey;
+    use crate::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::enums::overflow_policy::OverflowPolicy;
+    use crate::io::input::InputChannel;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use futures_core::Stream;
+    use std::error::Error;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Poll, Wake, Waker};
+
+    /// Counts how many times it was woken up
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Presses the pads of notes 0 to 3 on a stream buffering two events, returns the pads left and the dropped count
+    fn overfill(overflow: OverflowPolicy) -> Result<(Vec<PadKey>, u64), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut stream = InputChannel::from_source(device.input()).into_stream(
+            PadsAndKnobsChannel,
+            2,
+            overflow,
+        )?;
+        for note in 0..4 {
+            device.send(&[0x90, note, 0x7F]);
+        }
+        let waker = Waker::from(Arc::new(CountingWaker::default()));
+        let mut cx = Context::from_waker(&waker);
+        let mut pads = Vec::new();
+        while let Poll::Ready(Some(event)) = Pin::new(&mut stream).poll_next(&mut cx) {
+            if let PadsAndKnobsInputGroup::Pads(pad) = event.input_group {
+                pads.push(pad);
+            }
+        }
+        Ok((pads, stream.dropped()))
+    }
+
+    #[test]
+    fn drop_oldest_keeps_the_latest_events() -> Result<(), Box<dyn Error>> {
+        let (pads, dropped) = overfill(OverflowPolicy::DropOldest)?;
+        assert_eq!(pads, vec![PadKey::from_note(2)?, PadKey::from_note(3)?]);
+        assert_eq!(dropped, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn drop_newest_keeps_the_first_events() -> Result<(), Box<dyn Error>> {
+        let (pads, dropped) = overfill(OverflowPolicy::DropNewest)?;
+        assert_eq!(pads, vec![PadKey::from_note(0)?, PadKey::from_note(1)?]);
+        assert_eq!(dropped, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn pending_stream_is_woken_by_the_next_event() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut stream = InputChannel::from_source(device.input()).into_stream(
+            PadsAndKnobsChannel,
+            8,
+            OverflowPolicy::default(),
+        )?;
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 0);
+        device.send(&[0x90, 0x27, 0x7F]);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        device.send(&[0x80, 0x27, 0x00]);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert!(matches!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(event)) if event.is_pressed()
+        ));
+        assert!(matches!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(event)) if event.is_released()
+        ));
+        assert_eq!(stream.dropped(), 0);
+        Ok(())
+    }
+}
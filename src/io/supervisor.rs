@@ -0,0 +1,349 @@
+use crate::enums::input_group::ChannelKind;
+use crate::enums::message_filter::MessageFilter;
+use crate::enums::port_selector::PortSelector;
+use crate::errors::io::{ChannelCreationError, TransmissionError};
+use crate::io::input::InputChannel;
+use crate::io::input_data::MidiInputData;
+use crate::io::output::ChannelOutput;
+use crate::io::sync::lock;
+use crate::io::transport::hardware::Midir;
+use crate::io::transport::{MidiBackend, MidiSink, MidiSource};
+use bon::bon;
+use midir::MidiOutputConnection;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+type SharedOutput<O> = Arc<Mutex<ChannelOutput<SupervisedOutput<O>>>>;
+type EventHandler = Box<dyn FnMut(ConnectionEvent) + Send>;
+
+#[derive(Debug)]
+pub enum ConnectionEvent {
+    Connected,
+    Disconnected,
+    /// The device is visible but could not be opened, it will be retried on the next poll
+    ConnectionFailed(ChannelCreationError),
+}
+
+/// Output transport that survives the device going away.
+///
+/// While disconnected, `send` returns `TransmissionError::Disconnected`. The LED writes are still recorded by
+/// `ChannelOutput::leds`, which is sent again on reconnection
+pub struct SupervisedOutput<O = MidiOutputConnection> {
+    connection: Option<O>,
+}
+
+impl<O> Default for SupervisedOutput<O> {
+    fn default() -> Self {
+        Self { connection: None }
+    }
+}
+
+impl<O> SupervisedOutput<O> {
+    #[must_use]
+    pub const fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    fn attach(&mut self, connection: O) {
+        self.connection = Some(connection);
+    }
+
+    fn detach(&mut self) {
+        self.connection = None;
+    }
+}
+
+impl<O: MidiSink> MidiSink for SupervisedOutput<O> {
+    fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError> {
+        self.connection.as_mut().map_or_else(
+            || {
+                Err(TransmissionError::Disconnected {
+                    data: data.to_vec(),
+                })
+            },
+            |connection| MidiSink::send(connection, data),
+        )
+    }
+}
+
+/// Keeps the controller connected for long-running applications.
+///
+/// A background thread polls the available ports, reopens both directions when the device comes back,
+/// re-sends the LED initialization with the last known LED state and registers the listener again.
+/// Dropping the supervisor stops the thread and closes the connections
+/// ```Rust
+///let supervisor = ramidier::io::supervisor::DeviceSupervisor::builder()
+///    .channel_type(PadsAndKnobsChannel)
+///    .listener(|stamp, received_input, midi_out| listener_logic(midi_out, stamp, &received_input))
+///    .on_event(Box::new(|event| println!("{event:?}")))
+///    .poll_interval(Duration::from_secs(1))
+///    .build();
+/// ```
+pub struct DeviceSupervisor<B: MidiBackend = Midir> {
+    output: SharedOutput<B::Sink>,
+    running: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+#[bon]
+impl DeviceSupervisor {
+    /// Supervises the ports exposed by the platform, see `with_backend` for the parameters
+    #[builder]
+    pub fn new<C, F>(
+        channel_type: C,
+        listener: F,
+        on_event: Option<EventHandler>,
+        port_selector: Option<PortSelector>,
+        msg_to_ignore: Option<MessageFilter>,
+        poll_interval: Option<Duration>,
+    ) -> Self
+    where
+        C: ChannelKind + Copy + Send + 'static,
+        F: FnMut(u64, MidiInputData<C::Group>, &mut ChannelOutput<SupervisedOutput>)
+            + Send
+            + 'static,
+    {
+        Self::with_backend(Midir)
+            .channel_type(channel_type)
+            .listener(listener)
+            .maybe_on_event(on_event)
+            .maybe_port_selector(port_selector)
+            .maybe_msg_to_ignore(msg_to_ignore)
+            .maybe_poll_interval(poll_interval)
+            .build()
+    }
+}
+
+#[bon]
+impl<B> DeviceSupervisor<B>
+where
+    B: MidiBackend + Send + 'static,
+    B::Sink: Send + 'static,
+{
+    /// Supervises the ports of any backend, e.g. a `LoopbackDevice` that is unplugged and plugged in again
+    /// ```Rust
+    ///let supervisor = DeviceSupervisor::with_backend(loopback.clone())
+    ///    .channel_type(PadsAndKnobsChannel)
+    ///    .listener(|_, _, _| ())
+    ///    .build();
+    /// ```
+    #[builder(finish_fn = build)]
+    pub fn with_backend<C, F>(
+        #[builder(start_fn)] backend: B,
+        channel_type: C,
+        listener: F,
+        on_event: Option<EventHandler>,
+        port_selector: Option<PortSelector>,
+        msg_to_ignore: Option<MessageFilter>,
+        poll_interval: Option<Duration>,
+    ) -> Self
+    where
+        C: ChannelKind + Copy + Send + 'static,
+        F: FnMut(u64, MidiInputData<C::Group>, &mut ChannelOutput<SupervisedOutput<B::Sink>>)
+            + Send
+            + 'static,
+    {
+        let output: SharedOutput<B::Sink> = Arc::new(Mutex::new(ChannelOutput::from_sink(
+            SupervisedOutput::default(),
+        )));
+        let running = Arc::new(AtomicBool::new(true));
+        let worker = Watcher {
+            backend,
+            channel_type,
+            listener: Arc::new(Mutex::new(listener)),
+            on_event: on_event.unwrap_or_else(|| Box::new(|_| ())),
+            selector: port_selector.unwrap_or_default(),
+            msg_to_ignore: msg_to_ignore.unwrap_or(MessageFilter::None),
+            poll_interval: poll_interval.unwrap_or(Duration::from_millis(500)),
+            output: Arc::clone(&output),
+            running: Arc::clone(&running),
+        };
+        Self {
+            output,
+            running,
+            worker: Some(thread::spawn(move || worker.run())),
+        }
+    }
+}
+
+impl<B: MidiBackend> DeviceSupervisor<B> {
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.output().transport().is_connected()
+    }
+
+    /// Exclusive access to the output, LED writes are remembered even while the device is unplugged
+    pub fn output(&self) -> MutexGuard<'_, ChannelOutput<SupervisedOutput<B::Sink>>> {
+        lock(&self.output)
+    }
+
+    /// Output handle for other threads, e.g. an `Animator`
+    #[must_use]
+    pub fn shared_output(&self) -> SharedOutput<B::Sink> {
+        Arc::clone(&self.output)
+    }
+}
+
+impl<B: MidiBackend> Drop for DeviceSupervisor<B> {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+struct Watcher<B: MidiBackend, C, F> {
+    backend: B,
+    channel_type: C,
+    listener: Arc<Mutex<F>>,
+    on_event: EventHandler,
+    selector: PortSelector,
+    msg_to_ignore: MessageFilter,
+    poll_interval: Duration,
+    output: SharedOutput<B::Sink>,
+    running: Arc<AtomicBool>,
+}
+
+impl<B, C, F> Watcher<B, C, F>
+where
+    B: MidiBackend,
+    C: ChannelKind + Copy + Send + 'static,
+    F: FnMut(u64, MidiInputData<C::Group>, &mut ChannelOutput<SupervisedOutput<B::Sink>>)
+        + Send
+        + 'static,
+    B::Sink: Send + 'static,
+{
+    fn run(mut self) {
+        let mut connection: Option<<B::Source as MidiSource>::Connection<()>> = None;
+        while self.running.load(Ordering::Relaxed) {
+            match (
+                connection.is_some(),
+                self.backend.is_available(&self.selector),
+            ) {
+                (false, true) => match self.connect() {
+                    Ok(conn) => {
+                        connection = Some(conn);
+                        (self.on_event)(ConnectionEvent::Connected);
+                    }
+                    Err(e) => {
+                        lock(&self.output).transport_mut().detach();
+                        (self.on_event)(ConnectionEvent::ConnectionFailed(e));
+                    }
+                },
+                (true, false) => {
+                    connection = None;
+                    lock(&self.output).transport_mut().detach();
+                    (self.on_event)(ConnectionEvent::Disconnected);
+                }
+                _ => (),
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+
+    fn connect(&self) -> Result<<B::Source as MidiSource>::Connection<()>, ChannelCreationError> {
+        let output_connection = self
+            .backend
+            .open_sink(&self.selector, "akai-midir-supervised-output")?;
+        {
+            let mut output = lock(&self.output);
+            output.transport_mut().attach(output_connection);
+            output
+                .initialize_notes_led()
+                .and_then(|()| output.resend_leds())
+                .map_err(|e| ChannelCreationError::LedInitialization { source: e })?;
+        }
+        let listener = Arc::clone(&self.listener);
+        let output = Arc::clone(&self.output);
+        InputChannel::from_source(
+            self.backend
+                .open_source(&self.selector, self.msg_to_ignore)?,
+        )
+        .listen(
+            Some("akai-midir-supervised-input"),
+            move |timestamp, input, ()| {
+                (lock(&listener))(timestamp, input, &mut lock(&output));
+            },
+            (),
+            self.channel_type,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionEvent, DeviceSupervisor};
+    use crate::enums::button::soft_keys::SoftKey;
+    use crate::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::enums::led_light::button_state::ButtonLedState;
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::errors::io::TransmissionError;
+    use crate::io::output::INIT_SYSEX;
+    use crate::io::transport::loopback::LoopbackDevice;
+    use std::error::Error;
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::Duration;
+
+    fn next_event(events: &Receiver<ConnectionEvent>) -> Result<ConnectionEvent, Box<dyn Error>> {
+        Ok(events.recv_timeout(Duration::from_secs(5))?)
+    }
+
+    #[test]
+    fn reconnection_initializes_then_restores_the_leds() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let (tx, events) = mpsc::channel();
+        let supervisor = DeviceSupervisor::with_backend(device.clone())
+            .channel_type(PadsAndKnobsChannel)
+            .listener(|_, input, midi_out| {
+                if let PadsAndKnobsInputGroup::Pads(pad) = input.input_group {
+                    let _ = midi_out.set_pad_led(LedMode::On100Percent, pad, LedColor::Green);
+                }
+            })
+            .on_event(Box::new(move |event| {
+                let _ = tx.send(event);
+            }))
+            .poll_interval(Duration::from_millis(1))
+            .build();
+        assert!(matches!(next_event(&events)?, ConnectionEvent::Connected));
+        assert_eq!(device.take_received()[0], INIT_SYSEX);
+
+        device.unplug();
+        assert!(matches!(
+            next_event(&events)?,
+            ConnectionEvent::Disconnected
+        ));
+        assert!(!supervisor.is_connected());
+        assert!(matches!(
+            supervisor
+                .output()
+                .set_pad_led(LedMode::Pulsing1over8, 3, LedColor::Red),
+            Err(TransmissionError::Disconnected { .. })
+        ));
+        assert!(
+            supervisor
+                .output()
+                .set_button_led(SoftKey::Mute, ButtonLedState::On)
+                .is_err()
+        );
+        assert!(device.received().is_empty());
+
+        device.plug_in();
+        assert!(matches!(next_event(&events)?, ConnectionEvent::Connected));
+        let expected: Vec<Vec<u8>> = std::iter::once(INIT_SYSEX.to_vec())
+            .chain(supervisor.output().leds().diff(None))
+            .collect();
+        let received = device.take_received();
+        assert_eq!(received, expected);
+        assert!(received.contains(&vec![0x98, 0x03, 0x05]));
+        assert!(received.contains(&vec![0x90, u8::from(SoftKey::Mute), 0x01]));
+
+        assert_eq!(device.listener_count(), 1);
+        device.send(&[0x90, 0x27, 0x7F]);
+        assert_eq!(device.received(), vec![vec![0x96, 0x27, 0x15]]);
+        Ok(())
+    }
+}
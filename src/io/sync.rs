@@ -0,0 +1,6 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A panicking listener must not take the whole device down, so poisoned locks are recovered
+pub fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
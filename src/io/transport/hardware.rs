@@ -0,0 +1,88 @@
+use crate::enums::message_filter::MessageFilter;
+use crate::enums::port_selector::PortSelector;
+use crate::errors::io::{ChannelCreationError, TransmissionError};
+use crate::io::channel::{Channel, select_port};
+use crate::io::input::InputChannel;
+use crate::io::output::ChannelOutput;
+use crate::io::transport::{MidiBackend, MidiSink, MidiSource};
+use midir::{MidiInput, MidiInputConnection, MidiInputPort, MidiOutputConnection};
+
+/// Ports exposed by the platform MIDI API, through midir
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Midir;
+
+impl MidiBackend for Midir {
+    type Source = MidirInput;
+    type Sink = MidiOutputConnection;
+
+    fn is_available(&self, selector: &PortSelector) -> bool {
+        InputChannel::get_midi().is_ok_and(|midi_in| select_port(&midi_in, selector).is_ok())
+    }
+
+    fn open_source(
+        &self,
+        selector: &PortSelector,
+        msg_to_ignore: MessageFilter,
+    ) -> Result<MidirInput, ChannelCreationError> {
+        let mut midi_in = InputChannel::get_midi()?;
+        midi_in.ignore(msg_to_ignore.into());
+        let input_port = select_port(&midi_in, selector)?;
+        Ok(MidirInput::new(midi_in, input_port))
+    }
+
+    fn open_sink(
+        &self,
+        selector: &PortSelector,
+        port_name: &str,
+    ) -> Result<MidiOutputConnection, ChannelCreationError> {
+        let midi_output = ChannelOutput::get_midi()?;
+        let out_port = select_port(&midi_output, selector)?;
+        midi_output
+            .connect(&out_port, port_name)
+            .map_err(|e| ChannelCreationError::EstablishingOutputConnection { source: e })
+    }
+}
+
+/// midir backed input, bound to the port chosen when building the `InputChannel`
+pub struct MidirInput {
+    midi_input: MidiInput,
+    input_port: MidiInputPort,
+}
+
+impl MidirInput {
+    #[must_use]
+    pub const fn new(midi_input: MidiInput, input_port: MidiInputPort) -> Self {
+        Self {
+            midi_input,
+            input_port,
+        }
+    }
+}
+
+impl MidiSource for MidirInput {
+    type Connection<T: Send + 'static> = MidiInputConnection<T>;
+
+    fn connect<F, T>(
+        self,
+        port_name: &str,
+        callback: F,
+        data: T,
+    ) -> Result<Self::Connection<T>, ChannelCreationError>
+    where
+        F: FnMut(u64, &[u8], &mut T) + Send + 'static,
+        T: Send + 'static,
+    {
+        self.midi_input
+            .connect(&self.input_port, port_name, callback, data)
+            .map_err(|e| ChannelCreationError::EstablishingInputConnection { source: e })
+    }
+}
+
+impl MidiSink for MidiOutputConnection {
+    fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError> {
+        Self::send(self, data).map_err(|e| TransmissionError::Send {
+            data: data.to_vec(),
+            source: e,
+        })
+    }
+}
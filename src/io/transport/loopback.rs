@@ -0,0 +1,351 @@
+use crate::enums::message_filter::MessageFilter;
+use crate::enums::port_selector::PortSelector;
+use crate::errors::io::{ChannelCreationError, TransmissionError};
+use crate::io::sync::lock;
+use crate::io::transport::{MidiBackend, MidiSink, MidiSource};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+type Handler = Arc<Mutex<dyn FnMut(u64, &[u8]) + Send>>;
+
+struct Shared {
+    epoch: Instant,
+    plugged_in: AtomicBool,
+    next_id: AtomicUsize,
+    listeners: Mutex<Vec<(usize, Handler)>>,
+    received: Mutex<Vec<Vec<u8>>>,
+}
+
+/// In-memory stand-in for the physical controller.
+///
+/// Bytes given to `send` reach every connected `LoopbackInput` listener as if the device had sent them,
+/// while everything written to a `LoopbackOutput` is recorded and can be inspected with `received`.
+/// ```Rust
+///let device = LoopbackDevice::new();
+///let midi_in = InputChannel::from_source(device.input());
+///let mut midi_out = ChannelOutput::from_sink(device.output());
+///let _conn_in = midi_in.listen(None, |_, rx_data, ()| println!("{rx_data:?}"), (), PadsAndKnobsChannel)?;
+///device.send(&[0x90, 0x27, 0x7F]); // press the top right pad
+///midi_out.set_pad_led(LedMode::On100Percent, 39, LedColor::Green)?;
+///assert_eq!(device.received(), vec![vec![0x96, 0x27, 0x15]]);
+/// ```
+#[derive(Clone)]
+pub struct LoopbackDevice {
+    shared: Arc<Shared>,
+}
+
+impl Default for LoopbackDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoopbackDevice {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                epoch: Instant::now(),
+                plugged_in: AtomicBool::new(true),
+                next_id: AtomicUsize::new(0),
+                listeners: Mutex::new(Vec::new()),
+                received: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Input side of the device, to be given to `InputChannel::from_source`
+    #[must_use]
+    pub fn input(&self) -> LoopbackInput {
+        LoopbackInput {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Output side of the device, to be given to `ChannelOutput::from_sink`
+    #[must_use]
+    pub fn output(&self) -> LoopbackOutput {
+        LoopbackOutput {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Delivers the given bytes to every open connection, as if the device had sent them.
+    /// Listeners are called on the current thread; a listener must not call `send` on the same device.
+    /// Nothing is delivered while the device is unplugged
+    pub fn send(&self, data: &[u8]) {
+        if !self.is_plugged_in() {
+            return;
+        }
+        let timestamp = self.shared.epoch.elapsed().as_micros() as u64;
+        let handlers: Vec<Handler> = lock(&self.shared.listeners)
+            .iter()
+            .map(|(_, h)| Arc::clone(h))
+            .collect();
+        for handler in handlers {
+            (lock(&handler))(timestamp, data);
+        }
+    }
+
+    /// Every message written to the device so far, oldest first
+    #[must_use]
+    pub fn received(&self) -> Vec<Vec<u8>> {
+        lock(&self.shared.received).clone()
+    }
+
+    /// Same as `received`, but also clears the recorded messages
+    #[must_use]
+    pub fn take_received(&self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut *lock(&self.shared.received))
+    }
+
+    /// Simulates pulling the cable: the device can no longer be opened, open connections go silent
+    /// and writes fail with `TransmissionError::Disconnected`
+    pub fn unplug(&self) {
+        self.shared.plugged_in.store(false, Ordering::Relaxed);
+    }
+
+    /// Undoes `unplug`, the device can be opened again
+    pub fn plug_in(&self) {
+        self.shared.plugged_in.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_plugged_in(&self) -> bool {
+        self.shared.plugged_in.load(Ordering::Relaxed)
+    }
+
+    fn check_plugged_in(&self, selector: &PortSelector) -> Result<(), ChannelCreationError> {
+        if self.is_plugged_in() {
+            Ok(())
+        } else {
+            Err(ChannelCreationError::NoMatchingPort {
+                selector: selector.clone(),
+                available: Vec::new(),
+            })
+        }
+    }
+
+    /// Number of connections currently listening to the device
+    #[must_use]
+    pub fn listener_count(&self) -> usize {
+        lock(&self.shared.listeners).len()
+    }
+}
+
+/// The device has a single port, matched by any selector
+impl MidiBackend for LoopbackDevice {
+    type Source = LoopbackInput;
+    type Sink = LoopbackOutput;
+
+    fn is_available(&self, _selector: &PortSelector) -> bool {
+        self.is_plugged_in()
+    }
+
+    fn open_source(
+        &self,
+        selector: &PortSelector,
+        _msg_to_ignore: MessageFilter,
+    ) -> Result<LoopbackInput, ChannelCreationError> {
+        self.check_plugged_in(selector)?;
+        Ok(self.input())
+    }
+
+    fn open_sink(
+        &self,
+        selector: &PortSelector,
+        _port_name: &str,
+    ) -> Result<LoopbackOutput, ChannelCreationError> {
+        self.check_plugged_in(selector)?;
+        Ok(self.output())
+    }
+}
+
+pub struct LoopbackInput {
+    shared: Arc<Shared>,
+}
+
+impl MidiSource for LoopbackInput {
+    type Connection<T: Send + 'static> = LoopbackInputConnection<T>;
+
+    fn connect<F, T>(
+        self,
+        _port_name: &str,
+        mut callback: F,
+        data: T,
+    ) -> Result<Self::Connection<T>, ChannelCreationError>
+    where
+        F: FnMut(u64, &[u8], &mut T) + Send + 'static,
+        T: Send + 'static,
+    {
+        let data = Arc::new(Mutex::new(Some(data)));
+        let handler_data = Arc::clone(&data);
+        let handler: Handler = Arc::new(Mutex::new(move |timestamp: u64, bytes: &[u8]| {
+            if let Some(user_data) = lock(&handler_data).as_mut() {
+                callback(timestamp, bytes, user_data);
+            }
+        }));
+        let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+        lock(&self.shared.listeners).push((id, handler));
+        Ok(LoopbackInputConnection {
+            id,
+            shared: self.shared,
+            data,
+        })
+    }
+}
+
+/// Keeps the listener registered on the `LoopbackDevice` until closed or dropped
+pub struct LoopbackInputConnection<T> {
+    id: usize,
+    shared: Arc<Shared>,
+    data: Arc<Mutex<Option<T>>>,
+}
+
+impl<T> LoopbackInputConnection<T> {
+    /// Stops listening and gives back the user data
+    #[must_use]
+    pub fn close(self) -> Option<T> {
+        lock(&self.data).take()
+    }
+}
+
+impl<T> Drop for LoopbackInputConnection<T> {
+    fn drop(&mut self) {
+        lock(&self.shared.listeners).retain(|(id, _)| *id != self.id);
+    }
+}
+
+pub struct LoopbackOutput {
+    shared: Arc<Shared>,
+}
+
+impl MidiSink for LoopbackOutput {
+    fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError> {
+        if !self.shared.plugged_in.load(Ordering::Relaxed) {
+            return Err(TransmissionError::Disconnected {
+                data: data.to_vec(),
+            });
+        }
+        lock(&self.shared.received).push(data.to_vec());
+        Ok(())
+    }
+}
+
+/// Feeds `messages` to a listener decoding `channel_type` and returns the user data once the connection is closed
+#[cfg(test)]
+pub(crate) fn listen_to<C, T, F, M>(
+    channel_type: C,
+    messages: &[M],
+    data: T,
+    mut handler: F,
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    C: crate::enums::input_group::ChannelKind + Send + 'static,
+    T: Send + 'static,
+    F: FnMut(crate::io::input_data::MidiInputData<C::Group>, &mut T) + Send + 'static,
+    M: AsRef<[u8]>,
+{
+    let device = LoopbackDevice::new();
+    let conn = crate::io::input::InputChannel::from_source(device.input()).listen(
+        None,
+        move |_, input, data: &mut T| handler(input, data),
+        data,
+        channel_type,
+    )?;
+    for message in messages {
+        device.send(message.as_ref());
+    }
+    Ok(conn.close().ok_or("no user data")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoopbackDevice, listen_to};
+    use crate::enums::button::pads::{PAD_COUNT, PadKey};
+    use crate::enums::input_group::{PadsAndKnobsChannel, PadsAndKnobsInputGroup};
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::io::input::InputChannel;
+    use crate::io::input_data::InputAction;
+    use crate::io::output::ChannelOutput;
+    use std::error::Error;
+
+    const INIT_SYSEX: [u8; 12] = [
+        0xF0, 0x47, 0x7F, 0x29, 0x60, 0x00, 0x04, 0x42, 0x08, 0x02, 0x01, 0xF7,
+    ];
+
+    #[test]
+    fn listen_decodes_what_the_device_sends() -> Result<(), Box<dyn Error>> {
+        let received = listen_to(
+            PadsAndKnobsChannel,
+            &[[0x90, 0x27, 0x7F], [0x80, 0x27, 0x00], [0xB0, 0x30, 0x01]],
+            Vec::new(),
+            |input, received| received.push((input.input_group, input.action)),
+        )?;
+        assert_eq!(
+            received,
+            vec![
+                (
+                    PadsAndKnobsInputGroup::Pads(PadKey::Grid(1, 8)),
+                    InputAction::Pressed { velocity: 0x7F }
+                ),
+                (
+                    PadsAndKnobsInputGroup::Pads(PadKey::Grid(1, 8)),
+                    InputAction::Released { velocity: 0 }
+                ),
+                (
+                    PadsAndKnobsInputGroup::Knob(1),
+                    InputAction::Changed { value: 1 }
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_the_connection_stops_listening() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let conn = InputChannel::from_source(device.input()).listen_raw(None, |_, _, ()| (), ())?;
+        assert_eq!(device.listener_count(), 1);
+        drop(conn);
+        assert_eq!(device.listener_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn set_pad_led_sends_mode_note_and_color() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        midi_out.set_pad_led(LedMode::On100Percent, 39, LedColor::Green)?;
+        midi_out.set_pad_led(LedMode::Blinking1over2, PadKey::Grid(5, 1), LedColor::Red)?;
+        assert_eq!(
+            device.take_received(),
+            vec![vec![0x96, 0x27, 0x15], vec![0x9F, 0x00, 0x05]]
+        );
+        assert!(device.received().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn initialize_notes_led_sends_the_init_message() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        ChannelOutput::from_sink(device.output()).initialize_notes_led()?;
+        assert_eq!(device.received(), vec![INIT_SYSEX.to_vec()]);
+        Ok(())
+    }
+
+    #[test]
+    fn set_all_pads_color_initializes_then_sets_every_pad() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        ChannelOutput::from_sink(device.output())
+            .set_all_pads_color(LedMode::On50Percent, LedColor::Blue1)?;
+        let received = device.received();
+        let expected: Vec<Vec<u8>> = std::iter::once(INIT_SYSEX.to_vec())
+            .chain((0..PAD_COUNT).map(|note| vec![0x92, note, 0x29]))
+            .collect();
+        assert_eq!(received, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+pub mod hardware;
+pub mod loopback;
+
+use crate::enums::message_filter::MessageFilter;
+use crate::enums::port_selector::PortSelector;
+use crate::errors::io::{ChannelCreationError, TransmissionError};
+
+/// Something the controller messages can be read from, e.g. a midir input port or an in-memory device.
+/// Connecting consumes the source and returns a connection that keeps the callback alive until dropped.
+pub trait MidiSource {
+    type Connection<T: Send + 'static>;
+
+    /// # Errors
+    ///
+    /// Will return `ChannelCreationError` if there are low-level issues communicating with the device
+    fn connect<F, T>(
+        self,
+        port_name: &str,
+        callback: F,
+        data: T,
+    ) -> Result<Self::Connection<T>, ChannelCreationError>
+    where
+        F: FnMut(u64, &[u8], &mut T) + Send + 'static,
+        T: Send + 'static;
+}
+
+/// Something the controller messages can be written to, e.g. a midir output connection or an in-memory device.
+pub trait MidiSink {
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if data is not valid or there are low-level issues communicating with the device
+    fn send(&mut self, data: &[u8]) -> Result<(), TransmissionError>;
+}
+
+/// Finds the controller among the available ports and opens both directions on it, e.g. midir or a `LoopbackDevice`.
+/// `DeviceSupervisor` polls it to notice the device going away and coming back
+pub trait MidiBackend {
+    type Source: MidiSource;
+    type Sink: MidiSink;
+
+    /// Checks whether a port matching the selector is currently exposed
+    fn is_available(&self, selector: &PortSelector) -> bool;
+
+    /// # Errors
+    ///
+    /// Will return `ChannelCreationError` if no port matches or there are low-level issues communicating with the device
+    fn open_source(
+        &self,
+        selector: &PortSelector,
+        msg_to_ignore: MessageFilter,
+    ) -> Result<Self::Source, ChannelCreationError>;
+
+    /// # Errors
+    ///
+    /// Will return `ChannelCreationError` if no port matches or there are low-level issues communicating with the device
+    fn open_sink(
+        &self,
+        selector: &PortSelector,
+        port_name: &str,
+    ) -> Result<Self::Sink, ChannelCreationError>;
+}
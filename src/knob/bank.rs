@@ -0,0 +1,310 @@
+use crate::enums::button::knob_ctrl::KnobCtrlKey;
+use crate::enums::input_group::PadsAndKnobsInputGroup;
+use crate::enums::led_light::button_state::ButtonLedState;
+use crate::errors::io::TransmissionError;
+use crate::errors::mapping::HardwareMappingError;
+use crate::io::frame::LedFrame;
+use crate::io::input_data::InputAction;
+use crate::io::output::ChannelOutput;
+use crate::io::transport::MidiSink;
+use crate::knob::binding::{KnobBindings, ParameterChange};
+use crate::knob::encoder::{KNOB_COUNT, knob_slot};
+
+/// Banks in the order of the buttons on the device
+pub const KNOB_BANKS: [KnobCtrlKey; 4] = [
+    KnobCtrlKey::Volume,
+    KnobCtrlKey::Pan,
+    KnobCtrlKey::Send,
+    KnobCtrlKey::Device,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BankEvent<'a> {
+    /// One of the knob control buttons was pressed, the knobs now drive its bank and its button is lit
+    BankSelected(KnobCtrlKey),
+    ParameterChanged {
+        bank: KnobCtrlKey,
+        /// Logical parameter, from 0 to `KNOB_BANKS.len() * KNOB_COUNT` - 1
+        parameter: u8,
+        change: ParameterChange<'a>,
+    },
+}
+
+const fn bank_slot(bank: KnobCtrlKey) -> usize {
+    match bank {
+        KnobCtrlKey::Volume => 0,
+        KnobCtrlKey::Pan => 1,
+        KnobCtrlKey::Send => 2,
+        KnobCtrlKey::Device => 3,
+    }
+}
+
+/// Routes the 8 knobs to one of 4 banks of `KnobBindings`, selected with the Volume, Pan, Send and Device buttons.
+///
+/// Every bank keeps its own parameter values, switching back and forth does not lose them.
+/// `process` lights the button of the selected bank, call `show` once to light the initial one
+/// ```Rust
+///let mut banks = KnobBanks::default();
+///banks.bank_mut(KnobCtrlKey::Volume).bind(1, master_volume)?;
+///banks.bank_mut(KnobCtrlKey::Pan).bind(1, master_pan)?;
+///banks.show(&mut device.output())?;
+///device.listen(
+///    move |_, rx_data, midi_out, ()| {
+///        if let Ok(Some(BankEvent::ParameterChanged { change, .. })) =
+///            banks.process(rx_data.input_group, rx_data.action, midi_out)
+///        {
+///            println!("{change}");
+///        }
+///    },
+///    (),
+///    PadsAndKnobsChannel,
+/// )?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct KnobBanks {
+    active: KnobCtrlKey,
+    banks: [KnobBindings; KNOB_BANKS.len()],
+}
+
+impl Default for KnobBanks {
+    fn default() -> Self {
+        Self {
+            active: KnobCtrlKey::Volume,
+            banks: Default::default(),
+        }
+    }
+}
+
+impl KnobBanks {
+    #[must_use]
+    pub const fn active(&self) -> KnobCtrlKey {
+        self.active
+    }
+
+    pub const fn select(&mut self, bank: KnobCtrlKey) {
+        self.active = bank;
+    }
+
+    #[must_use]
+    pub const fn bank(&self, bank: KnobCtrlKey) -> &KnobBindings {
+        &self.banks[bank_slot(bank)]
+    }
+
+    pub const fn bank_mut(&mut self, bank: KnobCtrlKey) -> &mut KnobBindings {
+        &mut self.banks[bank_slot(bank)]
+    }
+
+    /// Logical parameter driven by the knob in the given bank
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn parameter(bank: KnobCtrlKey, knob: u8) -> Result<u8, HardwareMappingError> {
+        knob_slot(knob).map(|slot| bank_slot(bank) as u8 * KNOB_COUNT + slot as u8)
+    }
+
+    /// Switches bank on knob control presses, lighting the button of the new bank, and updates the active bank
+    /// on knob turns. Anything else is ignored
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if the bank buttons cannot be lit, the bank is switched anyway
+    pub fn process<O: MidiSink>(
+        &mut self,
+        input_group: PadsAndKnobsInputGroup,
+        action: InputAction,
+        output: &mut ChannelOutput<O>,
+    ) -> Result<Option<BankEvent<'_>>, TransmissionError> {
+        match (input_group, action) {
+            (PadsAndKnobsInputGroup::KnobCtrl(bank), InputAction::Pressed { .. }) => {
+                self.select(bank);
+                self.show(output)?;
+                Ok(Some(BankEvent::BankSelected(bank)))
+            }
+            (PadsAndKnobsInputGroup::Knob(knob), InputAction::Changed { .. }) => {
+                let bank = self.active;
+                let Ok(parameter) = Self::parameter(bank, knob) else {
+                    return Ok(None);
+                };
+                Ok(self
+                    .bank_mut(bank)
+                    .process(input_group, action)
+                    .map(|change| BankEvent::ParameterChanged {
+                        bank,
+                        parameter,
+                        change,
+                    }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Lights the button of the active bank and turns the other ones off, `process` does it on every bank switch
+    /// # Errors
+    ///
+    /// Will return `TransmissionError` if there are low-level issues communicating with the device
+    pub fn show<O: MidiSink>(
+        &self,
+        output: &mut ChannelOutput<O>,
+    ) -> Result<(), TransmissionError> {
+        KNOB_BANKS
+            .iter()
+            .try_for_each(|bank| output.set_button_led(*bank, self.led_state(*bank)))
+    }
+
+    /// Same as `show`, on a frame to be flushed later
+    pub fn render(&self, frame: &mut LedFrame) {
+        for bank in KNOB_BANKS {
+            frame.set_button(bank, self.led_state(bank));
+        }
+    }
+
+    fn led_state(&self, bank: KnobCtrlKey) -> ButtonLedState {
+        if bank == self.active {
+            ButtonLedState::On
+        } else {
+            ButtonLedState::Off
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BankEvent, KnobBanks};
+    use crate::enums::button::knob_ctrl::KnobCtrlKey;
+    use crate::enums::input_group::PadsAndKnobsInputGroup;
+    use crate::io::input_data::InputAction;
+    use crate::io::output::ChannelOutput;
+    use crate::io::transport::loopback::{LoopbackDevice, LoopbackOutput};
+    use crate::knob::binding::KnobBinding;
+    use std::error::Error;
+
+    const PRESSED: InputAction = InputAction::Pressed { velocity: 0x7F };
+
+    /// One unit per knob increment
+    fn steps(name: &str) -> KnobBinding {
+        KnobBinding::builder()
+            .name(name)
+            .max(128.0)
+            .resolution(128)
+            .build()
+    }
+
+    fn press(
+        banks: &mut KnobBanks,
+        bank: KnobCtrlKey,
+        midi_out: &mut ChannelOutput<LoopbackOutput>,
+    ) -> Result<(), Box<dyn Error>> {
+        banks.process(PadsAndKnobsInputGroup::KnobCtrl(bank), PRESSED, midi_out)?;
+        Ok(())
+    }
+
+    /// Turns the first knob by `increments` and returns the logical parameter and its new value
+    fn turn(
+        banks: &mut KnobBanks,
+        increments: u8,
+        midi_out: &mut ChannelOutput<LoopbackOutput>,
+    ) -> Option<(KnobCtrlKey, u8, f32)> {
+        match banks
+            .process(
+                PadsAndKnobsInputGroup::Knob(1),
+                InputAction::Changed { value: increments },
+                midi_out,
+            )
+            .ok()??
+        {
+            BankEvent::ParameterChanged {
+                bank,
+                parameter,
+                change,
+            } => Some((bank, parameter, change.value)),
+            BankEvent::BankSelected(_) => None,
+        }
+    }
+
+    #[test]
+    fn knob_ctrl_presses_switch_banks_and_light_their_button() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut banks = KnobBanks::default();
+        assert_eq!(banks.active(), KnobCtrlKey::Volume);
+        assert_eq!(
+            banks.process(
+                PadsAndKnobsInputGroup::KnobCtrl(KnobCtrlKey::Send),
+                PRESSED,
+                &mut midi_out
+            )?,
+            Some(BankEvent::BankSelected(KnobCtrlKey::Send))
+        );
+        assert_eq!(banks.active(), KnobCtrlKey::Send);
+        assert_eq!(
+            device.take_received(),
+            vec![
+                vec![0x90, 68, 0x00],
+                vec![0x90, 69, 0x00],
+                vec![0x90, 70, 0x01],
+                vec![0x90, 71, 0x00]
+            ]
+        );
+        assert_eq!(
+            banks.process(
+                PadsAndKnobsInputGroup::KnobCtrl(KnobCtrlKey::Pan),
+                InputAction::Released { velocity: 0 },
+                &mut midi_out
+            )?,
+            None
+        );
+        assert_eq!(banks.active(), KnobCtrlKey::Send);
+        assert!(device.received().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn banks_keep_their_values_across_switches() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut banks = KnobBanks::default();
+        banks
+            .bank_mut(KnobCtrlKey::Volume)
+            .bind(1, steps("Volume"))?;
+        banks.bank_mut(KnobCtrlKey::Pan).bind(1, steps("Pan"))?;
+        assert_eq!(
+            turn(&mut banks, 10, &mut midi_out),
+            Some((KnobCtrlKey::Volume, 0, 10.0))
+        );
+        press(&mut banks, KnobCtrlKey::Pan, &mut midi_out)?;
+        assert_eq!(
+            turn(&mut banks, 3, &mut midi_out),
+            Some((KnobCtrlKey::Pan, 8, 3.0))
+        );
+        press(&mut banks, KnobCtrlKey::Device, &mut midi_out)?;
+        assert_eq!(turn(&mut banks, 3, &mut midi_out), None);
+        press(&mut banks, KnobCtrlKey::Volume, &mut midi_out)?;
+        assert_eq!(
+            turn(&mut banks, 1, &mut midi_out),
+            Some((KnobCtrlKey::Volume, 0, 11.0))
+        );
+        assert_eq!(
+            banks.bank(KnobCtrlKey::Pan).get(1)?.map(KnobBinding::value),
+            Some(3.0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn show_lights_only_the_active_bank() -> Result<(), Box<dyn Error>> {
+        let device = LoopbackDevice::new();
+        let mut midi_out = ChannelOutput::from_sink(device.output());
+        let mut banks = KnobBanks::default();
+        banks.select(KnobCtrlKey::Send);
+        banks.show(&mut midi_out)?;
+        assert_eq!(
+            device.received(),
+            vec![
+                vec![0x90, 68, 0x00],
+                vec![0x90, 69, 0x00],
+                vec![0x90, 70, 0x01],
+                vec![0x90, 71, 0x00]
+            ]
+        );
+        Ok(())
+    }
+}
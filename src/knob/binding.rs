@@ -0,0 +1,322 @@
+use crate::enums::input_group::PadsAndKnobsInputGroup;
+use crate::errors::mapping::HardwareMappingError;
+use crate::io::input_data::InputAction;
+use crate::knob::encoder::{Acceleration, KNOB_COUNT, KnobEncoder, knob_slot, relative_delta};
+use bon::bon;
+use std::fmt;
+
+/// Portion of the knob travel, around each detent, that snaps to the detent value
+const DETENT_WIDTH: f32 = 0.02;
+
+/// How the knob position is spread over the parameter range
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    /// Equal knob travel for equal ratios, e.g. every octave of a 20 Hz..20 kHz range.
+    /// Falls back to `Linear` unless both ends of the range are strictly positive
+    Logarithmic,
+    /// Position raised to the given exponent, values above 1 give finer control at the low end of the range
+    Exponential(f32),
+}
+
+impl ResponseCurve {
+    fn map(self, position: f32, min: f32, max: f32) -> f32 {
+        match self {
+            Self::Logarithmic if min > 0.0 && max > 0.0 => min * (max / min).powf(position),
+            Self::Exponential(exponent) => position.powf(exponent).mul_add(max - min, min),
+            _ => position.mul_add(max - min, min),
+        }
+    }
+
+    fn position(self, value: f32, min: f32, max: f32) -> f32 {
+        let position = match self {
+            Self::Logarithmic if min > 0.0 && max > 0.0 => (value / min).log(max / min),
+            Self::Exponential(exponent) => ((value - min) / (max - min)).powf(exponent.recip()),
+            _ => (value - min) / (max - min),
+        };
+        if position.is_finite() {
+            position.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Named parameter driven by a knob, mapping its position to a value in the parameter range and unit
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnobBinding {
+    name: String,
+    unit: String,
+    min: f32,
+    max: f32,
+    curve: ResponseCurve,
+    detents: Vec<f32>,
+    encoder: KnobEncoder,
+}
+
+#[bon]
+impl KnobBinding {
+    /// `resolution` is the number of knob increments needed to sweep the whole range, 128 by default
+    /// ```Rust
+    ///let cutoff = KnobBinding::builder()
+    ///    .name("Cutoff")
+    ///    .unit("Hz")
+    ///    .min(20.0)
+    ///    .max(20_000.0)
+    ///    .curve(ResponseCurve::Logarithmic)
+    ///    .detents(vec![440.0])
+    ///    .build();
+    /// ```
+    #[builder]
+    pub fn new(
+        #[builder(into)] name: String,
+        #[builder(into)] unit: Option<String>,
+        min: Option<f32>,
+        max: Option<f32>,
+        curve: Option<ResponseCurve>,
+        detents: Option<Vec<f32>>,
+        resolution: Option<u16>,
+        acceleration: Option<Acceleration>,
+        initial_value: Option<f32>,
+    ) -> Self {
+        let min = min.unwrap_or(0.0);
+        let max = max.unwrap_or(1.0);
+        let curve = curve.unwrap_or_default();
+        let encoder = KnobEncoder::builder()
+            .min(0.0)
+            .max(1.0)
+            .step(f32::from(resolution.unwrap_or(128).max(1)).recip())
+            .maybe_acceleration(acceleration)
+            .initial_value(curve.position(initial_value.unwrap_or(min), min, max))
+            .build();
+        Self {
+            name,
+            unit: unit.unwrap_or_default(),
+            min,
+            max,
+            curve,
+            detents: detents.unwrap_or_default(),
+            encoder,
+        }
+    }
+}
+
+impl KnobBinding {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    /// Knob position, from 0 to 1
+    #[must_use]
+    pub const fn position(&self) -> f32 {
+        self.encoder.value()
+    }
+
+    /// Current value in the parameter range, snapped to a detent when close enough
+    #[must_use]
+    pub fn value(&self) -> f32 {
+        let position = self.position();
+        self.detents
+            .iter()
+            .copied()
+            .find(|d| {
+                (self.curve.position(*d, self.min, self.max) - position).abs() <= DETENT_WIDTH
+            })
+            .unwrap_or_else(|| self.curve.map(position, self.min, self.max))
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.encoder
+            .set_value(self.curve.position(value, self.min, self.max));
+    }
+
+    /// Applies the raw CC value sent by the knob, returns the new value if it moved
+    pub fn apply(&mut self, raw_value: u8) -> Option<f32> {
+        let previous = self.value();
+        self.encoder.apply_delta(relative_delta(raw_value))?;
+        let value = self.value();
+        ((value - previous).abs() > f32::EPSILON).then_some(value)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParameterChange<'a> {
+    /// From 1 to `KNOB_COUNT`, as in `PadsAndKnobsInputGroup::Knob`
+    pub knob: u8,
+    pub name: &'a str,
+    pub unit: &'a str,
+    pub value: f32,
+}
+
+impl fmt::Display for ParameterChange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:.2} {}", self.name, self.value, self.unit)
+    }
+}
+
+/// Up to one `KnobBinding` per knob, fed with the decoded input of any listener
+/// ```Rust
+///let mut bindings = KnobBindings::default();
+///bindings.bind(1, cutoff)?;
+///let _conn_in = midi_in.listen(
+///    None,
+///    move |_, rx_data, ()| {
+///        if let Some(change) = bindings.process(rx_data.input_group, rx_data.action) {
+///            println!("{change}"); // Cutoff: 440.00 Hz
+///        }
+///    },
+///    (),
+///    PadsAndKnobsChannel,
+/// )?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KnobBindings {
+    bindings: [Option<KnobBinding>; KNOB_COUNT as usize],
+}
+
+impl KnobBindings {
+    /// Binds the knob to the parameter, returning the previous binding
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn bind(
+        &mut self,
+        knob: u8,
+        binding: KnobBinding,
+    ) -> Result<Option<KnobBinding>, HardwareMappingError> {
+        knob_slot(knob).map(|slot| self.bindings[slot].replace(binding))
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn unbind(&mut self, knob: u8) -> Result<Option<KnobBinding>, HardwareMappingError> {
+        knob_slot(knob).map(|slot| self.bindings[slot].take())
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn get(&self, knob: u8) -> Result<Option<&KnobBinding>, HardwareMappingError> {
+        knob_slot(knob).map(|slot| self.bindings[slot].as_ref())
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn get_mut(&mut self, knob: u8) -> Result<Option<&mut KnobBinding>, HardwareMappingError> {
+        knob_slot(knob).map(|slot| self.bindings[slot].as_mut())
+    }
+
+    /// Updates the parameter bound to the knob that was turned, anything else is ignored
+    pub fn process(
+        &mut self,
+        input_group: PadsAndKnobsInputGroup,
+        action: InputAction,
+    ) -> Option<ParameterChange<'_>> {
+        let (PadsAndKnobsInputGroup::Knob(knob), InputAction::Changed { value }) =
+            (input_group, action)
+        else {
+            return None;
+        };
+        let binding = self.get_mut(knob).ok()??;
+        binding.apply(value).map(|value| ParameterChange {
+            knob,
+            name: &binding.name,
+            unit: &binding.unit,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KnobBinding, KnobBindings, ResponseCurve};
+    use crate::enums::input_group::PadsAndKnobsChannel;
+    use crate::io::transport::loopback::listen_to;
+    use std::error::Error;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() <= expected.abs().max(1.0) * 1e-4,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn curves_map_the_middle_of_the_travel() {
+        assert_close(ResponseCurve::Linear.map(0.5, 20.0, 20_000.0), 10_010.0);
+        assert_close(
+            ResponseCurve::Logarithmic.map(0.5, 20.0, 20_000.0),
+            632.455_5,
+        );
+        assert_close(ResponseCurve::Exponential(2.0).map(0.5, 0.0, 100.0), 25.0);
+        assert_close(ResponseCurve::Logarithmic.map(0.5, 0.0, 100.0), 50.0);
+    }
+
+    #[test]
+    fn position_is_the_inverse_of_map() {
+        for curve in [
+            ResponseCurve::Linear,
+            ResponseCurve::Logarithmic,
+            ResponseCurve::Exponential(3.0),
+        ] {
+            for position in [0.0, 0.25, 0.5, 1.0] {
+                let value = curve.map(position, 20.0, 20_000.0);
+                assert_close(curve.position(value, 20.0, 20_000.0), position);
+            }
+        }
+        assert_close(ResponseCurve::Linear.position(-5.0, 0.0, 1.0), 0.0);
+        assert_close(ResponseCurve::Linear.position(1.0, 1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn values_near_a_detent_snap_to_it() {
+        let mut binding = KnobBinding::builder()
+            .name("Pan")
+            .min(-1.0)
+            .max(1.0)
+            .detents(vec![0.0])
+            .resolution(100)
+            .initial_value(-0.05)
+            .build();
+        assert_close(binding.value(), -0.05);
+        assert_close(binding.apply(1).unwrap_or_default(), 0.0);
+        assert_eq!(binding.apply(1), None);
+        assert_close(binding.apply(3).unwrap_or_default(), 0.05);
+    }
+
+    #[test]
+    fn bindings_follow_the_knobs_sent_by_the_device() -> Result<(), Box<dyn Error>> {
+        let mut bindings = KnobBindings::default();
+        bindings.bind(
+            2,
+            KnobBinding::builder()
+                .name("Volume")
+                .unit("dB")
+                .min(-60.0)
+                .max(0.0)
+                .resolution(60)
+                .build(),
+        )?;
+        let (_, changes) = listen_to(
+            PadsAndKnobsChannel,
+            &[[0xB0, 0x31, 0x0A], [0xB0, 0x30, 0x0A]],
+            (bindings, Vec::new()),
+            |input, (bindings, changes): &mut (KnobBindings, Vec<String>)| {
+                if let Some(change) = bindings.process(input.input_group, input.action) {
+                    changes.push(change.to_string());
+                }
+            },
+        )?;
+        assert_eq!(changes, vec!["Volume: -50.00 dB".to_owned()]);
+        Ok(())
+    }
+}
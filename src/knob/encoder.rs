@@ -0,0 +1,264 @@
+use crate::enums::input_group::PadsAndKnobsInputGroup;
+use crate::errors::mapping::HardwareMappingError;
+use crate::io::input_data::InputAction;
+use bon::bon;
+
+/// Number of knobs, reported by `PadsAndKnobsInputGroup::Knob` from 1 to `KNOB_COUNT`
+pub const KNOB_COUNT: u8 = 8;
+
+/// The knobs are endless encoders: 1..=63 are clockwise increments, 127..=64 counterclockwise ones
+#[must_use]
+pub fn relative_delta(value: u8) -> i8 {
+    let value = i16::from(value & 0x7F);
+    (if value < 64 { value } else { value - 128 }) as i8
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Acceleration {
+    /// Every tick moves by `step`, no matter how fast the knob is turned
+    Constant,
+    /// Moves by `step` times the increment sent by the device, which grows with the turning speed
+    #[default]
+    Linear,
+    /// Moves by `step` times the increment raised to the given exponent, fast turns cover a much wider range
+    Power(f32),
+}
+
+impl Acceleration {
+    fn scale(self, delta: i8) -> f32 {
+        let magnitude = f32::from(delta.unsigned_abs());
+        let scaled = match self {
+            Self::Constant => magnitude.min(1.0),
+            Self::Linear => magnitude,
+            Self::Power(exponent) => magnitude.powf(exponent),
+        };
+        scaled.copysign(f32::from(delta))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KnobChange {
+    /// From 1 to `KNOB_COUNT`, as in `PadsAndKnobsInputGroup::Knob`
+    pub knob: u8,
+    pub delta: i8,
+    pub value: f32,
+}
+
+/// Accumulates the relative increments of a single knob into an absolute value
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KnobEncoder {
+    min: f32,
+    max: f32,
+    step: f32,
+    acceleration: Acceleration,
+    value: f32,
+}
+
+impl Default for KnobEncoder {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+#[bon]
+impl KnobEncoder {
+    /// Defaults to a 0..=127 range, starting from `min`, moving by 1 per increment with `Acceleration::Linear`
+    #[builder]
+    pub fn new(
+        min: Option<f32>,
+        max: Option<f32>,
+        step: Option<f32>,
+        acceleration: Option<Acceleration>,
+        initial_value: Option<f32>,
+    ) -> Self {
+        let min = min.unwrap_or(0.0);
+        let max = max.unwrap_or(127.0).max(min);
+        Self {
+            min,
+            max,
+            step: step.unwrap_or(1.0),
+            acceleration: acceleration.unwrap_or_default(),
+            value: initial_value.unwrap_or(min).clamp(min, max),
+        }
+    }
+}
+
+impl KnobEncoder {
+    /// Applies the raw CC value sent by the knob, returns the new value if it moved
+    pub fn apply(&mut self, raw_value: u8) -> Option<f32> {
+        self.apply_delta(relative_delta(raw_value))
+    }
+
+    /// Applies an already decoded increment, returns the new value if it moved
+    pub fn apply_delta(&mut self, delta: i8) -> Option<f32> {
+        let new_value = self
+            .acceleration
+            .scale(delta)
+            .mul_add(self.step, self.value)
+            .clamp(self.min, self.max);
+        if (new_value - self.value).abs() > f32::EPSILON {
+            self.value = new_value;
+            Some(new_value)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub const fn set_value(&mut self, value: f32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    #[must_use]
+    pub const fn range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+}
+
+/// One `KnobEncoder` per knob, fed with the decoded input of any listener
+/// ```Rust
+///let mut knobs = KnobEncoders::default();
+///knobs.set(1, KnobEncoder::builder().min(-1.0).max(1.0).step(0.01).build())?;
+///let _conn_in = midi_in.listen(
+///    None,
+///    move |_, rx_data, ()| {
+///        if let Some(change) = knobs.process(rx_data.input_group, rx_data.action) {
+///            println!("knob {} is now {}", change.knob, change.value);
+///        }
+///    },
+///    (),
+///    PadsAndKnobsChannel,
+/// )?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KnobEncoders {
+    knobs: [KnobEncoder; KNOB_COUNT as usize],
+}
+
+impl KnobEncoders {
+    /// Same configuration for every knob
+    #[must_use]
+    pub const fn uniform(encoder: KnobEncoder) -> Self {
+        Self {
+            knobs: [encoder; KNOB_COUNT as usize],
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn set(&mut self, knob: u8, encoder: KnobEncoder) -> Result<(), HardwareMappingError> {
+        *self.get_mut(knob)? = encoder;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn get(&self, knob: u8) -> Result<&KnobEncoder, HardwareMappingError> {
+        knob_slot(knob).map(|slot| &self.knobs[slot])
+    }
+
+    /// # Errors
+    ///
+    /// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+    pub fn get_mut(&mut self, knob: u8) -> Result<&mut KnobEncoder, HardwareMappingError> {
+        knob_slot(knob).map(|slot| &mut self.knobs[slot])
+    }
+
+    /// Updates the knob that was turned, anything else is ignored
+    pub fn process(
+        &mut self,
+        input_group: PadsAndKnobsInputGroup,
+        action: InputAction,
+    ) -> Option<KnobChange> {
+        let (PadsAndKnobsInputGroup::Knob(knob), InputAction::Changed { value }) =
+            (input_group, action)
+        else {
+            return None;
+        };
+        let delta = relative_delta(value);
+        self.get_mut(knob)
+            .ok()?
+            .apply_delta(delta)
+            .map(|value| KnobChange { knob, delta, value })
+    }
+}
+
+/// Position of the knob in arrays of `KNOB_COUNT` elements
+/// # Errors
+///
+/// Will return `HardwareMappingError` if the knob is not between 1 and `KNOB_COUNT`
+pub const fn knob_slot(knob: u8) -> Result<usize, HardwareMappingError> {
+    match knob {
+        1..=KNOB_COUNT => Ok((knob - 1) as usize),
+        _ => Err(HardwareMappingError::InvalidKnobIndex(knob)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Acceleration, KnobEncoder, KnobEncoders, relative_delta};
+    use crate::enums::input_group::PadsAndKnobsChannel;
+    use crate::errors::mapping::HardwareMappingError;
+    use crate::io::transport::loopback::listen_to;
+    use std::error::Error;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn relative_delta_splits_the_range_in_two() {
+        assert_eq!(relative_delta(0), 0);
+        assert_eq!(relative_delta(1), 1);
+        assert_eq!(relative_delta(63), 63);
+        assert_eq!(relative_delta(64), -64);
+        assert_eq!(relative_delta(127), -1);
+        assert_eq!(relative_delta(0x81), 1);
+    }
+
+    #[test]
+    fn encoder_clamps_and_reports_only_moves() {
+        let mut encoder = KnobEncoder::builder().min(0.0).max(10.0).step(2.0).build();
+        assert_eq!(encoder.apply(127), None);
+        assert_close(encoder.apply(3).unwrap_or_default(), 6.0);
+        assert_close(encoder.apply(63).unwrap_or_default(), 10.0);
+        assert_eq!(encoder.apply(1), None);
+    }
+
+    #[test]
+    fn acceleration_scales_the_increment() {
+        let moved = |acceleration| {
+            let mut encoder = KnobEncoder::builder().acceleration(acceleration).build();
+            encoder.apply(4).unwrap_or_default()
+        };
+        assert_close(moved(Acceleration::Constant), 1.0);
+        assert_close(moved(Acceleration::Linear), 4.0);
+        assert_close(moved(Acceleration::Power(2.0)), 16.0);
+    }
+
+    #[test]
+    fn encoders_follow_the_knobs_sent_by_the_device() -> Result<(), Box<dyn Error>> {
+        let knobs = listen_to(
+            PadsAndKnobsChannel,
+            &[[0xB0, 0x30, 0x05], [0xB0, 0x37, 0x02], [0xB0, 0x37, 0x7F]],
+            KnobEncoders::default(),
+            |input, knobs| {
+                knobs.process(input.input_group, input.action);
+            },
+        )?;
+        assert_close(knobs.get(1)?.value(), 5.0);
+        assert_close(knobs.get(8)?.value(), 1.0);
+        assert_close(knobs.get(2)?.value(), 0.0);
+        assert!(matches!(
+            knobs.get(9),
+            Err(HardwareMappingError::InvalidKnobIndex(9))
+        ));
+        Ok(())
+    }
+}
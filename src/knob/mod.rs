@@ -0,0 +1,3 @@
+pub mod bank;
+pub mod binding;
+pub mod encoder;
@@ -1,5 +1,8 @@
+pub mod animation;
 pub mod enums;
 pub mod errors;
 pub mod io;
+pub mod knob;
+pub mod text;
 
 pub use midi_msg;
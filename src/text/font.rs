@@ -0,0 +1,89 @@
+use crate::enums::button::pads::GRID_ROWS;
+
+/// Glyphs are as tall as the grid
+pub const FONT_HEIGHT: u8 = GRID_ROWS;
+
+/// Character of the bitmap font, between 1 and 5 pixels wide
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    width: u8,
+    /// One bit per pixel from the top row down, the most significant of the `width` bits is the leftmost column
+    rows: [u8; FONT_HEIGHT as usize],
+}
+
+impl Glyph {
+    const fn new(width: u8, rows: [u8; FONT_HEIGHT as usize]) -> Self {
+        Self { width, rows }
+    }
+
+    #[must_use]
+    pub const fn width(self) -> u8 {
+        self.width
+    }
+
+    /// Whether the pixel is part of the character, `row` 0 is the top
+    #[must_use]
+    pub const fn is_lit(self, column: u8, row: u8) -> bool {
+        column < self.width
+            && row < FONT_HEIGHT
+            && self.rows[row as usize] >> (self.width - 1 - column) & 1 == 1
+    }
+}
+
+/// Glyph of the character, lowercase letters are drawn as uppercase and unsupported characters as `?`
+#[must_use]
+pub const fn glyph(character: char) -> Glyph {
+    match character.to_ascii_uppercase() {
+        '0' => Glyph::new(3, [0b111, 0b101, 0b101, 0b101, 0b111]),
+        '1' => Glyph::new(3, [0b010, 0b110, 0b010, 0b010, 0b111]),
+        '2' => Glyph::new(3, [0b111, 0b001, 0b111, 0b100, 0b111]),
+        '3' => Glyph::new(3, [0b111, 0b001, 0b111, 0b001, 0b111]),
+        '4' => Glyph::new(3, [0b101, 0b101, 0b111, 0b001, 0b001]),
+        '5' => Glyph::new(3, [0b111, 0b100, 0b111, 0b001, 0b111]),
+        '6' => Glyph::new(3, [0b111, 0b100, 0b111, 0b101, 0b111]),
+        '7' => Glyph::new(3, [0b111, 0b001, 0b001, 0b010, 0b010]),
+        '8' => Glyph::new(3, [0b111, 0b101, 0b111, 0b101, 0b111]),
+        '9' => Glyph::new(3, [0b111, 0b101, 0b111, 0b001, 0b111]),
+        'A' => Glyph::new(3, [0b010, 0b101, 0b111, 0b101, 0b101]),
+        'B' => Glyph::new(3, [0b110, 0b101, 0b110, 0b101, 0b110]),
+        'C' => Glyph::new(3, [0b011, 0b100, 0b100, 0b100, 0b011]),
+        'D' => Glyph::new(3, [0b110, 0b101, 0b101, 0b101, 0b110]),
+        'E' => Glyph::new(3, [0b111, 0b100, 0b110, 0b100, 0b111]),
+        'F' => Glyph::new(3, [0b111, 0b100, 0b110, 0b100, 0b100]),
+        'G' => Glyph::new(3, [0b011, 0b100, 0b101, 0b101, 0b011]),
+        'H' => Glyph::new(3, [0b101, 0b101, 0b111, 0b101, 0b101]),
+        'I' => Glyph::new(3, [0b111, 0b010, 0b010, 0b010, 0b111]),
+        'J' => Glyph::new(3, [0b001, 0b001, 0b001, 0b101, 0b010]),
+        'K' => Glyph::new(3, [0b101, 0b101, 0b110, 0b101, 0b101]),
+        'L' => Glyph::new(3, [0b100, 0b100, 0b100, 0b100, 0b111]),
+        'M' => Glyph::new(5, [0b10001, 0b11011, 0b10101, 0b10001, 0b10001]),
+        'N' => Glyph::new(4, [0b1001, 0b1101, 0b1011, 0b1001, 0b1001]),
+        'O' => Glyph::new(3, [0b010, 0b101, 0b101, 0b101, 0b010]),
+        'P' => Glyph::new(3, [0b110, 0b101, 0b110, 0b100, 0b100]),
+        'Q' => Glyph::new(4, [0b0110, 0b1001, 0b1001, 0b1011, 0b0111]),
+        'R' => Glyph::new(3, [0b110, 0b101, 0b110, 0b101, 0b101]),
+        'S' => Glyph::new(3, [0b011, 0b100, 0b010, 0b001, 0b110]),
+        'T' => Glyph::new(3, [0b111, 0b010, 0b010, 0b010, 0b010]),
+        'U' => Glyph::new(3, [0b101, 0b101, 0b101, 0b101, 0b111]),
+        'V' => Glyph::new(3, [0b101, 0b101, 0b101, 0b101, 0b010]),
+        'W' => Glyph::new(5, [0b10001, 0b10001, 0b10101, 0b11011, 0b10001]),
+        'X' => Glyph::new(3, [0b101, 0b101, 0b010, 0b101, 0b101]),
+        'Y' => Glyph::new(3, [0b101, 0b101, 0b010, 0b010, 0b010]),
+        'Z' => Glyph::new(3, [0b111, 0b001, 0b010, 0b100, 0b111]),
+        ' ' => Glyph::new(2, [0b00, 0b00, 0b00, 0b00, 0b00]),
+        '.' => Glyph::new(1, [0b0, 0b0, 0b0, 0b0, 0b1]),
+        ',' => Glyph::new(2, [0b00, 0b00, 0b00, 0b01, 0b10]),
+        ':' => Glyph::new(1, [0b0, 0b1, 0b0, 0b1, 0b0]),
+        '!' => Glyph::new(1, [0b1, 0b1, 0b1, 0b0, 0b1]),
+        '\'' => Glyph::new(1, [0b1, 0b1, 0b0, 0b0, 0b0]),
+        '-' => Glyph::new(3, [0b000, 0b000, 0b111, 0b000, 0b000]),
+        '+' => Glyph::new(3, [0b000, 0b010, 0b111, 0b010, 0b000]),
+        '=' => Glyph::new(3, [0b000, 0b111, 0b000, 0b111, 0b000]),
+        '_' => Glyph::new(3, [0b000, 0b000, 0b000, 0b000, 0b111]),
+        '/' => Glyph::new(3, [0b001, 0b001, 0b010, 0b100, 0b100]),
+        '%' => Glyph::new(3, [0b101, 0b001, 0b010, 0b100, 0b101]),
+        '(' => Glyph::new(2, [0b01, 0b10, 0b10, 0b10, 0b01]),
+        ')' => Glyph::new(2, [0b10, 0b01, 0b01, 0b01, 0b10]),
+        _ => Glyph::new(3, [0b111, 0b001, 0b010, 0b000, 0b010]),
+    }
+}
@@ -0,0 +1,100 @@
+use crate::animation::effect::Effect;
+use crate::enums::button::pads::GRID_COLUMNS;
+use crate::io::frame::LedFrame;
+use crate::text::render::Text;
+use bon::bon;
+use std::time::Duration;
+
+/// Scrolls a `Text` from the right edge of the grid until it has left on the left edge
+/// ```Rust
+///let track = Text::builder().content("Track 12 - Lead").color(LedColor::Orange).build();
+///animator.play(Marquee::builder().text(track).speed(8.0).build());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Marquee {
+    text: Text,
+    speed: f32,
+    looping: bool,
+}
+
+#[bon]
+impl Marquee {
+    /// `speed` is in pads per second, 6 by default. Loops until stopped by default
+    #[builder]
+    pub fn new(text: Text, speed: Option<f32>, looping: Option<bool>) -> Self {
+        Self {
+            text,
+            speed: speed.unwrap_or(6.0),
+            looping: looping.unwrap_or(true),
+        }
+    }
+}
+
+impl Effect for Marquee {
+    fn render(&mut self, elapsed: Duration, frame: &mut LedFrame) -> bool {
+        #[allow(clippy::cast_sign_loss)]
+        let shift = (elapsed.as_secs_f32() * self.speed.max(0.0)) as usize;
+        let span = self.text.width() + usize::from(GRID_COLUMNS);
+        if !self.looping && shift >= span {
+            return false;
+        }
+        let scrolled = i32::try_from(shift % span).unwrap_or(i32::MAX);
+        self.text
+            .draw(frame, i32::from(GRID_COLUMNS).saturating_sub(scrolled));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Marquee;
+    use crate::animation::effect::Effect;
+    use crate::enums::button::pads::{GRID_ROWS, PadKey};
+    use crate::enums::led_light::color::LedColor;
+    use crate::io::frame::LedFrame;
+    use crate::text::render::Text;
+    use std::time::Duration;
+
+    /// Lit grid columns, from 1 on the left, after rendering at `millis`
+    fn lit_columns(marquee: &mut Marquee, millis: u64) -> (bool, Vec<u8>) {
+        let mut frame = LedFrame::default();
+        let running = marquee.render(Duration::from_millis(millis), &mut frame);
+        let columns = (1..=8)
+            .filter(|column| {
+                (1..=GRID_ROWS).any(|row| {
+                    frame
+                        .pad(PadKey::Grid(row, *column))
+                        .is_ok_and(|pad| pad.color != LedColor::Off)
+                })
+            })
+            .collect();
+        (running, columns)
+    }
+
+    fn one(looping: bool) -> Marquee {
+        Marquee::builder()
+            .text(Text::builder().content("1").build())
+            .speed(1.0)
+            .looping(looping)
+            .build()
+    }
+
+    #[test]
+    fn text_enters_on_the_right_and_leaves_on_the_left() {
+        let mut marquee = one(false);
+        assert_eq!(lit_columns(&mut marquee, 0), (true, vec![]));
+        assert_eq!(lit_columns(&mut marquee, 1_000), (true, vec![8]));
+        assert_eq!(lit_columns(&mut marquee, 3_500), (true, vec![6, 7, 8]));
+        assert_eq!(lit_columns(&mut marquee, 8_000), (true, vec![1, 2, 3]));
+        assert_eq!(lit_columns(&mut marquee, 9_000), (true, vec![1, 2]));
+        assert_eq!(lit_columns(&mut marquee, 10_999), (true, vec![1]));
+    }
+
+    #[test]
+    fn marquee_ends_once_the_text_has_left_unless_looping() {
+        assert_eq!(lit_columns(&mut one(false), 11_000), (false, vec![]));
+        let mut looping = one(true);
+        assert_eq!(lit_columns(&mut looping, 11_000), (true, vec![]));
+        assert_eq!(lit_columns(&mut looping, 12_000), (true, vec![8]));
+    }
+}
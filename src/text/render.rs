@@ -0,0 +1,180 @@
+use crate::enums::button::pads::{GRID_COLUMNS, PadKey};
+use crate::enums::led_light::color::LedColor;
+use crate::enums::led_light::mode::LedMode;
+use crate::io::frame::LedFrame;
+use crate::text::font::{FONT_HEIGHT, glyph};
+use bon::bon;
+
+/// String rendered with the bitmap font, ready to be drawn on the pad grid at any horizontal offset
+/// ```Rust
+///let bpm = Text::builder().content("96").color(LedColor::Green).build();
+///bpm.draw_centered(&mut frame_buffer);
+///frame_buffer.flush(&mut midi_out)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text {
+    /// One bit per row for every pixel column of the string, bit 0 is the top row
+    columns: Vec<u8>,
+    color: LedColor,
+    background: Option<LedColor>,
+    led_mode: LedMode,
+}
+
+#[bon]
+impl Text {
+    /// Characters are one pixel apart. Pads outside the characters are left untouched unless a `background` is given
+    #[builder]
+    pub fn new(
+        content: &str,
+        color: Option<LedColor>,
+        background: Option<LedColor>,
+        #[builder(into)] led_mode: Option<LedMode>,
+    ) -> Self {
+        let mut columns = Vec::new();
+        for (i, character) in content.chars().enumerate() {
+            if i > 0 {
+                columns.push(0);
+            }
+            let glyph = glyph(character);
+            columns.extend((0..glyph.width()).map(|column| {
+                (0..FONT_HEIGHT)
+                    .filter(|row| glyph.is_lit(column, *row))
+                    .fold(0, |mask, row| mask | 1 << row)
+            }));
+        }
+        Self {
+            columns,
+            color: color.unwrap_or(LedColor::White),
+            background,
+            led_mode: led_mode.unwrap_or_default(),
+        }
+    }
+}
+
+impl Text {
+    /// Width in pads
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Whether the whole string is visible at once, longer ones need a `Marquee`
+    #[must_use]
+    pub fn fits(&self) -> bool {
+        self.width() <= usize::from(GRID_COLUMNS)
+    }
+
+    /// Draws the string with its first column on grid column `x`, counted from 0 on the left.
+    /// Negative or large offsets show the part of the string that overlaps the grid
+    pub fn draw(&self, frame: &mut LedFrame, x: i32) {
+        for grid_column in 0..GRID_COLUMNS {
+            let mask = usize::try_from(i32::from(grid_column) - x)
+                .ok()
+                .and_then(|column| self.columns.get(column))
+                .copied();
+            for row in 0..FONT_HEIGHT {
+                let color = match mask {
+                    Some(mask) if mask >> row & 1 == 1 => Some(self.color),
+                    _ => self.background,
+                };
+                if let Some(color) = color {
+                    let pad = PadKey::Grid(row + 1, grid_column + 1);
+                    let _ = frame.set_pad(self.led_mode, pad, color);
+                }
+            }
+        }
+    }
+
+    /// Draws the string in the middle of the grid, or from the left edge when it does not fit
+    pub fn draw_centered(&self, frame: &mut LedFrame) {
+        let free = usize::from(GRID_COLUMNS).saturating_sub(self.width());
+        self.draw(frame, i32::try_from(free / 2).unwrap_or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Text;
+    use crate::enums::button::pads::{GRID_COLUMNS, GRID_ROWS, PadKey};
+    use crate::enums::led_light::color::LedColor;
+    use crate::enums::led_light::mode::LedMode;
+    use crate::io::frame::LedFrame;
+
+    /// One string per row, `#` for the text colour, `o` for any other colour and `.` for the pads left off
+    fn picture(frame: &LedFrame, color: LedColor) -> Vec<String> {
+        (1..=GRID_ROWS)
+            .map(|row| {
+                (1..=GRID_COLUMNS)
+                    .map(|column| match frame.pad(PadKey::Grid(row, column)) {
+                        Ok(pad) if pad.color == color => '#',
+                        Ok(pad) if pad.color != LedColor::Off => 'o',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn drawn(text: &Text, x: i32) -> Vec<String> {
+        let mut frame = LedFrame::default();
+        text.draw(&mut frame, x);
+        picture(&frame, LedColor::White)
+    }
+
+    #[test]
+    fn glyphs_are_placed_one_column_apart() {
+        let text = Text::builder().content("1-").build();
+        assert_eq!(text.width(), 7);
+        assert!(text.fits());
+        assert_eq!(
+            drawn(&text, 1),
+            vec!["..#.....", ".##.....", "..#..###", "..#.....", ".###....",]
+        );
+    }
+
+    #[test]
+    fn text_is_clipped_at_both_edges() {
+        let text = Text::builder().content("1").build();
+        assert_eq!(
+            drawn(&text, -1),
+            vec!["#.......", "#.......", "#.......", "#.......", "##......"]
+        );
+        assert_eq!(
+            drawn(&text, 6),
+            vec![".......#", "......##", ".......#", ".......#", "......##"]
+        );
+        assert_eq!(drawn(&text, 8), vec!["........"; 5]);
+        assert_eq!(drawn(&text, -3), vec!["........"; 5]);
+    }
+
+    #[test]
+    fn background_fills_the_rest_of_the_grid() {
+        let text = Text::builder()
+            .content(".")
+            .color(LedColor::Red)
+            .background(LedColor::Blue1)
+            .led_mode(LedMode::On50Percent)
+            .build();
+        let mut frame = LedFrame::default();
+        text.draw_centered(&mut frame);
+        assert_eq!(
+            picture(&frame, LedColor::Red),
+            vec!["oooooooo", "oooooooo", "oooooooo", "oooooooo", "ooo#oooo",]
+        );
+        assert!(
+            frame
+                .pad(PadKey::Grid(1, 1))
+                .is_ok_and(|pad| pad.mode == LedMode::On50Percent)
+        );
+    }
+
+    #[test]
+    fn long_text_is_drawn_from_the_left_edge() {
+        let text = Text::builder().content("100").build();
+        assert_eq!(text.width(), 11);
+        assert!(!text.fits());
+        let mut frame = LedFrame::default();
+        text.draw_centered(&mut frame);
+        assert_eq!(picture(&frame, LedColor::White), drawn(&text, 0));
+    }
+}